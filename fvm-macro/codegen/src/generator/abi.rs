@@ -0,0 +1,302 @@
+use crate::GenerateCode;
+use crate::generator::components;
+use derive_more::From;
+use heck::ToLowerCamelCase as _;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use serde_json::{
+    json,
+    Map,
+    Value,
+};
+
+use fvm_ir::ir::{
+    attrs::CallerPolicy,
+    item::state::StateItem,
+    contract,
+    item_impl::{
+        callable::{
+            Callable,
+            CallableWithSelector,
+        },
+        message,
+    },
+};
+
+/// Generates the JSON ABI of the contract as the `ABI` constant of the contract module.
+///
+/// The layout follows `metadata.json`: every dispatchable message is listed under `abi`
+/// with its method number and inputs, except for the messages of namespaced impl blocks, which
+/// are grouped by namespace under `namespaces`. The constructor is described under
+/// `constructor`.
+#[derive(From)]
+pub struct Abi<'a> {
+    contract: &'a contract::Contract,
+}
+
+impl core::convert::AsRef<contract::Contract> for Abi<'_> {
+    fn as_ref(&self) -> &contract::Contract {
+        self.contract
+    }
+}
+
+impl GenerateCode for Abi<'_> {
+    fn generate_code(&self) -> TokenStream2 {
+        let mut abi = json!({
+            "abi": self.messages(),
+            "constructor": self.constructor(),
+            "storage": self.storage(),
+        });
+        let namespaces = self.namespaces();
+        if !namespaces.is_empty() {
+            abi["namespaces"] = namespaces.into();
+        }
+        if let Some(fallback) = self.fallback() {
+            abi["fallback"] = fallback;
+        }
+        if let Some(instance) = self.contract.config().instantiate() {
+            abi["instantiate"] = json!(Self::tokens_to_string(instance));
+        }
+        if let Some(version) = self.contract.module().state().version() {
+            abi["version"] = json!(version);
+            abi["migrations"] = self.migrations().into();
+        }
+        let abi = abi.to_string();
+        let storage_layout = self.storage().to_string();
+        quote! {
+            /// The JSON ABI of this contract.
+            pub const ABI: &str = #abi;
+
+            /// The JSON storage layout of the state, also listed under `storage` in the ABI.
+            ///
+            /// Compare it with the layout of a previous version through `fvm_macro::layout`.
+            pub const STORAGE_LAYOUT: &str = #storage_layout;
+        }
+    }
+}
+
+impl Abi<'_> {
+    fn messages(&self) -> Vec<Value> {
+        self.contract
+            .module()
+            .dispatched_messages()
+            .into_iter()
+            .filter(|(_, cws)| cws.item_impl().namespace().is_none())
+            .map(|(method_number, cws)| Self::message(method_number, cws))
+            .chain(components::messages(self.contract).iter().map(|message| {
+                json!({
+                    "method": message.ident.to_string().to_lower_camel_case(),
+                    "methodNumber": message.method_number().to_string(),
+                    "inputs": message
+                        .inputs
+                        .iter()
+                        .map(|(name, ty)| json!({ "type": ty, "name": name }))
+                        .collect::<Vec<_>>(),
+                    "component": message.component,
+                })
+            }))
+            .collect()
+    }
+
+    /// Groups the messages of the namespaced impl blocks by their namespace.
+    fn namespaces(&self) -> Map<String, Value> {
+        let mut namespaces = Map::new();
+        for (method_number, cws) in self.contract.module().dispatched_messages() {
+            if let Some(namespace) = cws.item_impl().namespace() {
+                namespaces
+                    .entry(namespace.as_str().to_string())
+                    .or_insert_with(|| json!([]))
+                    .as_array_mut()
+                    .expect("namespaces only hold arrays")
+                    .push(Self::message(method_number, cws));
+            }
+        }
+        namespaces
+    }
+
+    fn message(method_number: u64, cws: CallableWithSelector<'_, message::Message>) -> Value {
+        let mut message = json!({
+            "method": cws.ident().to_string().to_lower_camel_case(),
+            "methodNumber": method_number.to_string(),
+            "inputs": Self::inputs(cws.callable().params()),
+        });
+        if let Some(access) = Self::access(cws.callable()) {
+            message["access"] = access;
+        }
+        message
+    }
+
+    fn constructor(&self) -> Value {
+        let constructor = self.contract.module().constructor();
+        let caller = match constructor
+            .as_ref()
+            .map(|cws| cws.callable().caller_policy().clone())
+            .unwrap_or_default()
+        {
+            CallerPolicy::Init => json!({ "policy": "init" }),
+            CallerPolicy::Any => json!({ "policy": "any" }),
+            CallerPolicy::ActorId(actor_id) => {
+                json!({ "policy": "actor", "actorId": actor_id.to_string() })
+            }
+            CallerPolicy::Address(address) => json!({ "policy": "address", "address": address }),
+        };
+        let inputs = constructor
+            .map(|cws| Self::inputs(cws.inputs()))
+            .unwrap_or_default();
+        json!({
+            "methodNumber": "1",
+            "caller": caller,
+            "inputs": inputs,
+        })
+    }
+
+    /// Describes the storage layout of the state: its encoding, version and the persisted fields
+    /// in their serialization order, leaving out the transient fields and marking the immutable
+    /// ones. The fields of a tuple struct are named by their index, and the variants of an enum
+    /// state are listed in place of fields, typed by their fields.
+    ///
+    /// The layout is parsed by `fvm_macro::layout::StorageLayout`.
+    fn storage(&self) -> Value {
+        let state = self.contract.module().state();
+        let migrates_from = self
+            .contract
+            .module()
+            .migrations()
+            .into_iter()
+            .map(|migration| migration.from())
+            .collect::<Vec<_>>();
+        let (encoding, fields) = match state.item() {
+            StateItem::Enum(item_enum) => ("enum", Self::storage_variants(item_enum)),
+            // Serde encodes a single field tuple struct as its field and a unit struct as `null`.
+            StateItem::Struct(item_struct) => match &item_struct.fields {
+                syn::Fields::Unnamed(fields) if fields.unnamed.len() == 1 => ("newtype", self.storage_fields()),
+                syn::Fields::Unit => ("unit", self.storage_fields()),
+                _ => (state.encoding().as_str(), self.storage_fields()),
+            },
+        };
+        json!({
+            "encoding": encoding,
+            "version": state.version(),
+            "migratesFrom": migrates_from,
+            "fields": fields,
+        })
+    }
+
+    fn storage_variants(item_enum: &syn::ItemEnum) -> Vec<Value> {
+        item_enum
+            .variants
+            .iter()
+            .enumerate()
+            .map(|(index, variant)| {
+                json!({
+                    "index": index,
+                    "name": variant.ident.to_string(),
+                    "type": Self::tokens_to_string(&variant.fields),
+                })
+            })
+            .collect()
+    }
+
+    fn storage_fields(&self) -> Vec<Value> {
+        let state = self.contract.module().state();
+        let component_fields = components::parsed_fields(self.contract);
+        state
+            .fields()
+            .filter(|field| !state.is_transient(field))
+            .chain(component_fields.iter())
+            .enumerate()
+            .map(|(index, field)| {
+                let name = match &field.ident {
+                    Some(ident) => ident.to_string(),
+                    None => index.to_string(),
+                };
+                let mut entry = json!({
+                    "index": index,
+                    "name": name,
+                    "type": Self::tokens_to_string(&field.ty),
+                });
+                if state.is_immutable(field) {
+                    entry["immutable"] = json!(true);
+                }
+                entry
+            })
+            .collect()
+    }
+
+    /// Lists the state versions the contract migrates from on load.
+    fn migrations(&self) -> Vec<Value> {
+        self.contract
+            .module()
+            .migrations()
+            .into_iter()
+            .map(|migration| {
+                json!({
+                    "from": migration.from(),
+                    "function": migration.ident().to_string().to_lower_camel_case(),
+                })
+            })
+            .collect()
+    }
+
+    /// Describes the message handling unknown method numbers, which are passed to it together
+    /// with the raw params.
+    fn fallback(&self) -> Option<Value> {
+        self.contract.module().fallback().map(|cws| {
+            let mut fallback = json!({
+                "method": cws.ident().to_string().to_lower_camel_case(),
+            });
+            if let Some(access) = Self::access(cws.callable()) {
+                fallback["access"] = access;
+            }
+            fallback
+        })
+    }
+
+    fn access(message: &message::Message) -> Option<Value> {
+        if message.only().is_none()
+            && message.roles().is_empty()
+            && !message.is_when_not_paused()
+            && !message.is_nonreentrant()
+        {
+            return None;
+        }
+        let mut access = json!({});
+        if message.is_nonreentrant() {
+            access["nonreentrant"] = json!(true);
+        }
+        if message.is_when_not_paused() {
+            access["whenNotPaused"] = json!(true);
+        }
+        if let Some(only) = message.only() {
+            access["only"] = json!(only.to_string());
+        }
+        if !message.roles().is_empty() {
+            access["roles"] = json!(message
+                .roles()
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>());
+        }
+        Some(access)
+    }
+
+    fn inputs<'a>(inputs: impl Iterator<Item = &'a syn::PatType>) -> Vec<Value> {
+        inputs
+            .map(|pat_type| {
+                json!({
+                    "type": Self::tokens_to_string(&pat_type.ty),
+                    "name": Self::tokens_to_string(&pat_type.pat),
+                })
+            })
+            .collect()
+    }
+
+    /// Renders the tokens without whitespace, writing the paths to the items the macro refers to
+    /// through `::fvm_macro::__private`, such as the types of component fields, as their public
+    /// `fvm_macro::` paths.
+    fn tokens_to_string<T: quote::ToTokens>(tokens: &T) -> String {
+        let mut repr = tokens.to_token_stream().to_string();
+        repr.retain(|c| !c.is_whitespace());
+        repr.replace("::fvm_macro::__private::", "fvm_macro::")
+    }
+}
@@ -0,0 +1,25 @@
+use crate::GenerateCode;
+use derive_more::From;
+use fvm_ir::{
+    ir::blake2::Blake2x256Macro,
+    HexLiteral as _,
+};
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote_spanned;
+
+/// Generates the `[u8; 32]` array of the BLAKE2b-256 hash computed by `blake2x256!`.
+#[derive(From)]
+pub struct Blake2x256<'a> {
+    macro_input: &'a Blake2x256Macro,
+}
+
+impl GenerateCode for Blake2x256<'_> {
+    fn generate_code(&self) -> TokenStream2 {
+        let span = self.macro_input.input().span();
+        let hash_bytes = self
+            .macro_input
+            .hash()
+            .map(|byte| byte.hex_padded_suffixed());
+        quote_spanned!(span=> [ #( #hash_bytes ),* ] )
+    }
+}
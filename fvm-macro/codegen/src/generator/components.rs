@@ -0,0 +1,248 @@
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{
+    format_ident,
+    quote,
+};
+
+use fvm_ir::ir::{
+    contract,
+    selector::Selector,
+};
+
+/// A message generated for a built-in component of the contract.
+///
+/// The message becomes an inherent method of the state struct taking the raw `params` and is
+/// dispatched under a method number derived from `<component>::<ident>`.
+pub struct ComponentMessage {
+    pub component: &'static str,
+    pub ident: syn::Ident,
+    pub mutates: bool,
+    /// Whether the message takes the `Rooted` state as `state` instead of `self`, to run other
+    /// messages through `__dispatch`.
+    pub rooted: bool,
+    /// The `(name, type)` pairs of the CBOR encoded parameters, as listed in the ABI.
+    pub inputs: Vec<(&'static str, &'static str)>,
+    pub body: TokenStream2,
+}
+
+impl ComponentMessage {
+    fn new(component: &'static str, ident: &str, mutates: bool, body: TokenStream2) -> Self {
+        Self {
+            component,
+            ident: format_ident!("{}", ident),
+            mutates,
+            rooted: false,
+            inputs: Vec::new(),
+            body,
+        }
+    }
+
+    fn with_inputs(mut self, inputs: &[(&'static str, &'static str)]) -> Self {
+        self.inputs = inputs.to_vec();
+        self
+    }
+
+    fn rooted(mut self) -> Self {
+        self.rooted = true;
+        self
+    }
+
+    pub fn method_number(&self) -> u64 {
+        let input = format!("{}::{}", self.component, self.ident);
+        Selector::compute(input.as_bytes()).into_be_u32() as u64
+    }
+}
+
+/// Returns the fields the enabled components add to the state struct.
+pub fn fields(contract: &contract::Contract) -> Vec<TokenStream2> {
+    let mut fields = Vec::new();
+    if contract.module().state().is_ownable() {
+        fields.push(quote! { pub owner: ::core::option::Option<::fvm_macro::__private::fvm_shared::ActorID> });
+        fields.push(quote! { pub pending_owner: ::core::option::Option<::fvm_macro::__private::fvm_shared::ActorID> });
+    }
+    if contract.module().state().is_pausable() {
+        fields.push(quote! { pub paused: bool });
+    }
+    if contract.config().rbac() {
+        fields.push(quote! { pub rbac: ::fvm_macro::__private::rbac::Rbac });
+    }
+    if has_nonreentrant_messages(contract) {
+        fields.push(quote! { pub reentrancy_lock: bool });
+    }
+    fields
+}
+
+/// Returns the fields the enabled components add to the state struct, parsed.
+pub fn parsed_fields(contract: &contract::Contract) -> Vec<syn::Field> {
+    fields(contract)
+        .into_iter()
+        .map(|tokens| {
+            syn::parse::Parser::parse2(syn::Field::parse_named, tokens)
+                .expect("encountered invalid component field")
+        })
+        .collect()
+}
+
+/// Returns `true` if any message of the contract is marked `nonreentrant`, which adds the
+/// `reentrancy_lock` field to the state.
+fn has_nonreentrant_messages(contract: &contract::Contract) -> bool {
+    contract
+        .module()
+        .impls()
+        .flat_map(|item_impl| item_impl.iter_messages())
+        .any(|cws| cws.callable().is_nonreentrant())
+}
+
+/// Returns the statements initialising the components of the freshly constructed `state`.
+///
+/// `params` is `Some(&_params)` if the raw constructor params are not taken by the user's
+/// constructor and may name the initial account, see `access::initial_account`.
+pub fn constructor_init(contract: &contract::Contract, params: TokenStream2) -> TokenStream2 {
+    let ownable = contract.module().state().is_ownable().then(|| {
+        quote! {
+            ::fvm_macro::__private::ownable::initialize(&mut state.owner, initial_account);
+        }
+    });
+    let rbac = contract.config().rbac().then(|| {
+        quote! {
+            ::fvm_macro::__private::rbac::initialize(
+                &mut state.rbac,
+                ::fvm_macro::__private::rbac::DEFAULT_ADMIN_ROLE,
+                initial_account,
+            );
+        }
+    });
+    let pauser = (contract.config().rbac() && contract.module().state().is_pausable()).then(|| {
+        quote! {
+            ::fvm_macro::__private::rbac::initialize(
+                &mut state.rbac,
+                ::fvm_macro::__private::pausable::PAUSER_ROLE,
+                initial_account,
+            );
+        }
+    });
+    let initial_account = (ownable.is_some() || rbac.is_some()).then(|| {
+        quote! {
+            let initial_account = ::fvm_macro::__private::access::initial_account(#params);
+        }
+    });
+    quote! {
+        #initial_account
+        #ownable
+        #rbac
+        #pauser
+    }
+}
+
+/// Returns the statements writing the parts of the components of `state` stored outside of the
+/// state root, run before the state is serialized.
+pub fn flush(contract: &contract::Contract) -> TokenStream2 {
+    if contract.config().rbac() {
+        quote! { state.rbac.flush(); }
+    } else {
+        quote! {}
+    }
+}
+
+/// Returns the messages the enabled components add to the contract.
+pub fn messages(contract: &contract::Contract) -> Vec<ComponentMessage> {
+    let mut messages = Vec::new();
+    if contract.module().state().is_ownable() {
+        messages.extend([
+            ComponentMessage::new("Ownable", "owner", false, quote! {
+                ::fvm_macro::__private::ownable::messages::owner(&self.owner)
+            }),
+            ComponentMessage::new("Ownable", "transfer_ownership", true, quote! {
+                ::fvm_macro::__private::ownable::messages::transfer_ownership(&self.owner, &mut self.pending_owner, &params)
+            })
+            .with_inputs(&[("new_owner", "Address")]),
+            ComponentMessage::new("Ownable", "accept_ownership", true, quote! {
+                ::fvm_macro::__private::ownable::messages::accept_ownership(&mut self.owner, &mut self.pending_owner)
+            }),
+            ComponentMessage::new("Ownable", "renounce_ownership", true, quote! {
+                ::fvm_macro::__private::ownable::messages::renounce_ownership(&mut self.owner, &mut self.pending_owner)
+            }),
+        ]);
+    }
+    if contract.module().state().is_pausable() {
+        // Checked when parsing: a pausable state is ownable or the contract uses rbac.
+        let ensure_pauser = if contract.config().rbac() {
+            quote! {
+                self.rbac.ensure_role(
+                    ::fvm_macro::__private::pausable::PAUSER_ROLE,
+                    ::fvm_macro::__private::sdk::message::caller(),
+                );
+            }
+        } else {
+            quote! { ::fvm_macro::__private::ownable::ensure_owner(&self.owner); }
+        };
+        messages.extend([
+            ComponentMessage::new("Pausable", "paused", false, quote! {
+                ::fvm_macro::__private::pausable::messages::paused(&self.paused)
+            }),
+            ComponentMessage::new("Pausable", "pause", true, quote! {
+                #ensure_pauser
+                ::fvm_macro::__private::pausable::messages::pause(&mut self.paused)
+            }),
+            ComponentMessage::new("Pausable", "unpause", true, quote! {
+                #ensure_pauser
+                ::fvm_macro::__private::pausable::messages::unpause(&mut self.paused)
+            }),
+        ]);
+    }
+    if contract.config().rbac() {
+        let role_params = [("role", "String"), ("account", "Address")];
+        messages.extend([
+            ComponentMessage::new("Rbac", "grant_role", true, quote! {
+                ::fvm_macro::__private::rbac::messages::grant_role(&mut self.rbac, &params)
+            })
+            .with_inputs(&role_params),
+            ComponentMessage::new("Rbac", "revoke_role", true, quote! {
+                ::fvm_macro::__private::rbac::messages::revoke_role(&mut self.rbac, &params)
+            })
+            .with_inputs(&role_params),
+            ComponentMessage::new("Rbac", "renounce_role", true, quote! {
+                ::fvm_macro::__private::rbac::messages::renounce_role(&mut self.rbac, &params)
+            })
+            .with_inputs(&role_params),
+            ComponentMessage::new("Rbac", "set_role_admin", true, quote! {
+                ::fvm_macro::__private::rbac::messages::set_role_admin(&mut self.rbac, &params)
+            })
+            .with_inputs(&[("role", "String"), ("admin_role", "String")]),
+            ComponentMessage::new("Rbac", "has_role", false, quote! {
+                ::fvm_macro::__private::rbac::messages::has_role(&self.rbac, &params)
+            })
+            .with_inputs(&role_params),
+        ]);
+    }
+    if !contract.module().migrations().is_empty() {
+        // The state is migrated when it is loaded, so the message only has to save it.
+        let ensure_admin = if contract.config().rbac() {
+            quote! {
+                self.rbac.ensure_role(
+                    ::fvm_macro::__private::rbac::DEFAULT_ADMIN_ROLE,
+                    ::fvm_macro::__private::sdk::message::caller(),
+                );
+            }
+        } else if contract.module().state().is_ownable() {
+            quote! { ::fvm_macro::__private::ownable::ensure_owner(&self.owner); }
+        } else {
+            quote! {}
+        };
+        messages.push(ComponentMessage::new("Migration", "migrate", true, quote! {
+            #ensure_admin
+            ::core::option::Option::None
+        }));
+    }
+    if contract.config().batch() {
+        let batch_method = Selector::compute(b"Batch::batch").into_be_u32() as u64;
+        messages.push(
+            ComponentMessage::new("Batch", "batch", true, quote! {
+                ::fvm_macro::__private::batch::run(state, &params, #batch_method, Self::__dispatch)
+            })
+            .rooted()
+            .with_inputs(&[("calls", "Vec<(u64, RawBytes)>")]),
+        );
+    }
+    messages
+}
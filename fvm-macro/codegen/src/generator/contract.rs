@@ -1,7 +1,7 @@
 use proc_macro2::TokenStream as TokenStream2;
 use quote::quote;
 use derive_more::From;
-use fvm_ir::ir::{contract, item};
+use fvm_ir::ir::{config::Entry, contract, item};
 use crate::{
     generator,
     GenerateCode,
@@ -30,6 +30,28 @@ impl GenerateCode for Contract<'_> {
 
         let state = self.generate_code_using::<generator::state::State>();
         let item_impls = self.generate_code_using::<generator::item_impls::ItemImpls>();
+        let events = self.generate_code_using::<generator::events::Events>();
+        let abi = self.generate_code_using::<generator::abi::Abi>();
+        // The entry point is declared in the contract module, where the state and the type
+        // arguments of its instance resolve whatever their visibility.
+        let entry = match self.contract.config().instantiate() {
+            Some(ty) => quote! { <#ty> },
+            None => quote! { #state_ident },
+        };
+        let export = match self.contract.config().entry() {
+            Entry::Always => Some(quote! {}),
+            Entry::Never => None,
+            Entry::Feature(feature) => Some(quote! { #[cfg(feature = #feature)] }),
+        };
+        let export = export.map(|cfg| {
+            quote! {
+                #cfg
+                #[no_mangle]
+                pub fn invoke(id: u32) -> u32 {
+                     self::#ident::__invoke(id)
+                }
+            }
+        });
         let non_fvm_items = self
             .contract
             .module()
@@ -41,13 +63,18 @@ impl GenerateCode for Contract<'_> {
             #vis mod #ident {
                 #( #non_fvm_items )*
                 #state
+                #events
                 #item_impls
-            }
+                #abi
 
-            #[no_mangle]
-            pub fn invoke(id: u32) -> u32 {
-                 crate::#ident::#state_ident::invoke(id)
+                /// Dispatches the invocation to the state.
+                #[doc(hidden)]
+                pub fn __invoke(id: u32) -> u32 {
+                    #entry::invoke(id)
+                }
             }
+
+            #export
         }
     }
 }
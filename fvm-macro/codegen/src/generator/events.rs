@@ -0,0 +1,118 @@
+use crate::GenerateCode;
+use crate::generator::tuple;
+use derive_more::From;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{
+    quote,
+    quote_spanned,
+};
+use syn::spanned::Spanned as _;
+
+use fvm_ir::ir::{
+    attrs,
+    contract,
+    item::event,
+};
+
+/// Generates the event structs of the contract and their `Event` implementations.
+#[derive(From)]
+pub struct Events<'a> {
+    contract: &'a contract::Contract,
+}
+
+impl core::convert::AsRef<contract::Contract> for Events<'_> {
+    fn as_ref(&self) -> &contract::Contract {
+        self.contract
+    }
+}
+
+impl GenerateCode for Events<'_> {
+    fn generate_code(&self) -> TokenStream2 {
+        let events = self
+            .contract
+            .module()
+            .events()
+            .map(Self::generate_event);
+        quote! {
+            #( #events )*
+        }
+    }
+}
+
+impl Events<'_> {
+    fn generate_event(event: &event::Event) -> TokenStream2 {
+        let span = event.span();
+        let attrs = event.attrs();
+        let vis = event.vis();
+        let ident = event.ident();
+        let name = ident.to_string();
+        let anonymous = event.anonymous;
+        let fields = event.fields().map(|field| {
+            let attrs = field.attrs().into_iter().filter(|attr| !attr.path.is_ident("serde"));
+            let vis = field.vis();
+            let ident = field.ident();
+            let ty = field.ty();
+            quote_spanned!(field.span() =>
+                #( #attrs )*
+                #vis #ident: #ty
+            )
+        });
+        let generics = event.generics();
+        let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+        // A generic event only serializes for some type arguments.
+        let topic_types = event
+            .fields()
+            .filter(|field| field.is_topic)
+            .map(|field| field.ty())
+            .filter(|_| !generics.params.is_empty());
+        let serialize_bound = (!generics.params.is_empty())
+            .then_some(quote! { #ident #ty_generics: ::fvm_macro::__private::serde::Serialize, });
+        let predicates = where_clause.map(|where_clause| &where_clause.predicates).into_iter().flatten();
+        let topics = event
+            .fields()
+            .filter(|field| field.is_topic)
+            .map(|field| {
+                let ident = field.ident();
+                quote_spanned!(field.span() =>
+                    match ::fvm_macro::__private::RawBytes::serialize(&self.#ident) {
+                        ::core::result::Result::Ok(topic) => topic,
+                        ::core::result::Result::Err(err) => {
+                            ::fvm_macro::abort!(USR_SERIALIZATION, "failed to serialize event topic: {:?}", err)
+                        }
+                    }
+                )
+            });
+        // The serde attributes of the fields go to the tuple impls, the derives to the event
+        // unless it derives them itself.
+        let derives = [("Clone", quote! { ::core::clone::Clone }), ("Debug", quote! { ::core::fmt::Debug })]
+            .into_iter()
+            .filter(|(name, _)| !attrs::derives(attrs, name))
+            .map(|(_, path)| path)
+            .collect::<Vec<_>>();
+        let derives = (!derives.is_empty()).then_some(quote! { #[derive( #( #derives ),* )] });
+        let tuple_fields = event.fields().map(|field| field.field()).collect::<Vec<_>>();
+        let tuple_impls = tuple::generate_serde_impls(ident, generics, &tuple_fields, &[]);
+        quote_spanned!(span =>
+            #derives
+            #( #attrs )*
+            #vis struct #ident #generics #where_clause {
+                #( #fields ),*
+            }
+            #tuple_impls
+
+            impl #impl_generics ::fvm_macro::__private::Event for #ident #ty_generics
+            where
+                #serialize_bound
+                #( #topic_types: ::fvm_macro::__private::serde::Serialize, )*
+                #( #predicates, )*
+            {
+                const NAME: &'static str = #name;
+                const ANONYMOUS: bool = #anonymous;
+
+                fn topics(&self) -> ::std::vec::Vec<::fvm_macro::__private::RawBytes> {
+                    ::std::vec![ #( #topics ),* ]
+                }
+            }
+        )
+    }
+}
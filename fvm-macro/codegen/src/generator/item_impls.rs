@@ -80,9 +80,11 @@ impl ItemImpls<'_> {
             .trait_path()
             .expect("encountered missing trait path for trait impl block");
         let self_type = item_impl.self_type();
+        let generics = item_impl.generics();
+        let where_clause = &generics.where_clause;
         quote_spanned!(span =>
             #( #attrs )*
-            impl #trait_path for #self_type {
+            impl #generics #trait_path for #self_type #where_clause {
                 #( #messages )*
             }
         )
@@ -116,8 +118,7 @@ impl ItemImpls<'_> {
         quote_spanned!(span =>
             #( #attrs )*
             #vis fn #ident(#receiver #( , #inputs )* ) #output_arrow #output {
-                _ = #receiver.save();
-                #( #statements )*;
+                #( #statements )*
             }
         )
     }
@@ -133,18 +134,22 @@ impl ItemImpls<'_> {
         let constructors = item_impl
             .iter_constructors()
             .map(|cws| Self::generate_inherent_constructor(cws.callable()));
+        let migrations = item_impl.iter_migrations();
         let other_items = item_impl
             .items()
             .iter()
             .filter_map(impl_item::ImplItem::filter_map_other_item)
             .map(ToTokens::to_token_stream);
         let self_type = item_impl.self_type();
+        let generics = item_impl.generics();
+        let where_clause = &generics.where_clause;
 
         quote_spanned!(span =>
             #( #attrs )*
-            impl #self_type {
+            impl #generics #self_type #where_clause {
                 #( #constructors )*
                 #( #messages )*
+                #( #migrations )*
                 #( #other_items )*
             }
         )
@@ -1,3 +1,9 @@
 pub mod contract;
 pub mod state;
 pub mod item_impls;
+pub mod abi;
+pub mod events;
+pub mod components;
+pub mod tuple;
+pub mod blake2b;
+pub mod selector;
@@ -0,0 +1,41 @@
+use crate::GenerateCode;
+use derive_more::From;
+use fvm_ir::{
+    ir::selector,
+    HexLiteral as _,
+};
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote_spanned;
+
+/// Generates the `u32` of the selector computed by `selector_id!`, the method number of a
+/// message numbered by the hash of its input.
+#[derive(From)]
+pub struct SelectorId<'a> {
+    macro_input: &'a selector::SelectorMacro<selector::SelectorId>,
+}
+
+impl GenerateCode for SelectorId<'_> {
+    fn generate_code(&self) -> TokenStream2 {
+        let span = self.macro_input.input().span();
+        let selector_id = self
+            .macro_input
+            .selector()
+            .into_be_u32()
+            .hex_padded_suffixed();
+        quote_spanned!(span=> #selector_id )
+    }
+}
+
+/// Generates the `[u8; 4]` array of the selector computed by `selector_bytes!`.
+#[derive(From)]
+pub struct SelectorBytes<'a> {
+    macro_input: &'a selector::SelectorMacro<selector::SelectorBytes>,
+}
+
+impl GenerateCode for SelectorBytes<'_> {
+    fn generate_code(&self) -> TokenStream2 {
+        let span = self.macro_input.input().span();
+        let selector_bytes = self.macro_input.selector().hex_lits();
+        quote_spanned!(span=> [ #( #selector_bytes ),* ] )
+    }
+}
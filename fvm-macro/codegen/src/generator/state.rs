@@ -1,4 +1,5 @@
 use crate::GenerateCode;
+use crate::generator::{components, tuple};
 use derive_more::From;
 use proc_macro2::{
     TokenStream as TokenStream2,
@@ -12,7 +13,6 @@ use quote::{
 
 use fvm_ir::ir::{
     contract,
-    item_impl,
     item_impl::{
         callable::{
             Callable
@@ -20,8 +20,9 @@ use fvm_ir::ir::{
     },
 };
 
+use fvm_ir::ir::attrs::{CallerPolicy, Encoding};
+use fvm_ir::ir::item::state::StateItem;
 use fvm_ir::ir::item_impl::{message};
-use heck::ToLowerCamelCase as _;
 
 
 use syn::spanned::Spanned as _;
@@ -53,170 +54,679 @@ impl State<'_> {
         let span = storage.span();
         let ident = storage.ident();
         let attrs = storage.attrs();
-        let fields = storage.fields();
-
-        let item_impls1 = self
+        // Structs with named fields are tuple encoded by the impls of `generate_tuple_impls`,
+        // which take over the serde attributes of the fields.
+        let tuple_encoded = storage.has_named_fields() && storage.encoding() == Encoding::Tuple;
+        let fields = storage.fields().map(|field| {
+            let mut field = field.clone();
+            if tuple_encoded {
+                field.attrs.retain(|attr| !attr.path.is_ident("serde"));
+            }
+            if storage.encoding() == Encoding::Map && storage.is_transient(&field) {
+                quote! { #[serde(skip)] #field }
+            } else {
+                quote! { #field }
+            }
+        });
+        let message_arms = self
             .contract
             .module()
-            .impls()
-            .map(|item_impl| self.generate_item_impl1(item_impl)).collect::<Vec<_>>();
+            .dispatched_messages()
+            .into_iter()
+            .map(|(method_number, cws)| {
+                Self::generate_inherent_message1(quote! { #method_number }, self.contract, cws.callable())
+            })
+            .collect::<Vec<_>>();
 
-        let constructor_index = 1 as u64;
+        let constructor_index = 1u64;
+        let fallback_arm = match self.contract.module().fallback() {
+            Some(cws) => Self::generate_inherent_message1(quote! { _ }, self.contract, cws.callable()),
+            None => quote! { _ => ::fvm_macro::abort!(USR_UNHANDLED_MESSAGE, "unrecognized method"), },
+        };
+        let constructor = self.generate_constructor();
+        let component_fields = components::fields(self.contract);
+        let component_messages = components::messages(self.contract);
+        let component_methods = component_messages.iter().map(Self::generate_component_method);
+        let component_arms = component_messages.iter().map(Self::generate_component_arm);
+        let mutates = self.generate_mutates();
+        let default_impl = self.generate_default_impl();
+        let immutable = self.generate_immutable();
+        let derives = self.generate_derives(default_impl.is_none());
+        let vis = storage.vis();
+        let generics = storage.generics();
+        let declared_where = &generics.where_clause;
+        let declaration = match storage.item() {
+            StateItem::Struct(item_struct) => match &item_struct.fields {
+                syn::Fields::Named(_) => quote! {
+                    #vis struct #ident #generics #declared_where {
+                        #( #fields, )*
+                        #( #component_fields, )*
+                    }
+                },
+                syn::Fields::Unnamed(_) => quote! {
+                    #vis struct #ident #generics( #( #fields, )* ) #declared_where;
+                },
+                syn::Fields::Unit => quote! {
+                    #vis struct #ident #generics #declared_where;
+                },
+            },
+            StateItem::Enum(item_enum) => {
+                let variants = &item_enum.variants;
+                quote! {
+                    #vis enum #ident #generics #declared_where {
+                        #variants
+                    }
+                }
+            }
+        };
+        let (impl_generics, ty_generics, where_clause) = self.generate_impl_generics();
+        let tuple_impls = self.generate_tuple_impls();
+        let version = storage.version();
+        let decode_state = match version {
+            Some(_) => quote! {
+                let (state, migrated) = Self::__decode(&block);
+                if migrated {
+                    return ::fvm_macro::__private::root::Rooted::migrated(state, root);
+                }
+            },
+            None => quote! {
+                let state = match ::fvm_macro::__private::from_slice::<Self>(&block) {
+                    ::core::result::Result::Ok(state) => state,
+                    ::core::result::Result::Err(err) => {
+                        ::fvm_macro::abort!(USR_SERIALIZATION, "failed to deserialize state: {}", err)
+                    }
+                };
+            },
+        };
+        let stored = match version {
+            Some(version) => quote! {
+                &::fvm_macro::__private::version::Versioned { version: #version, state: &**state }
+            },
+            None => quote! { &**state },
+        };
+        let decode = self.generate_decode();
+        let component_flush = components::flush(self.contract);
+        let lazy_fields = storage
+            .fields()
+            .enumerate()
+            .filter(|(_, field)| storage.is_lazy(field))
+            .map(|(index, field)| match &field.ident {
+                Some(ident) => syn::Member::Named(ident.clone()),
+                None => syn::Member::Unnamed(syn::Index::from(index)),
+            });
+        let flush = quote! {
+            #component_flush
+            #( state.#lazy_fields.flush(); )*
+        };
+        let has_immutable = immutable.is_some();
+        let immutable_snapshot = has_immutable.then(|| {
+            quote! {
+                let immutable = mutates.then(|| state.__immutable());
+            }
+        });
+        let immutable_check = has_immutable.then(|| {
+            quote! {
+                if immutable != ::core::option::Option::Some(state.__immutable()) {
+                    ::fvm_macro::abort!(USR_ASSERTION_FAILED, "message changed an immutable field");
+                }
+            }
+        });
 
         quote_spanned!( span =>
+            #derives
             #(#attrs)*
-            #[derive(Serialize_tuple, Deserialize_tuple, Clone, Debug, Default)]
-            pub struct #ident {
-                #( #fields ),*
-            }
-            impl State for #ident {
-               fn load() -> Self {
+            #declaration
+            #tuple_impls
+            #default_impl
+            impl #impl_generics ::fvm_macro::__private::State for #ident #ty_generics #where_clause {
+               fn load() -> ::fvm_macro::__private::root::Rooted<Self> {
                       // First, load the current state root.
-                      let root = match sdk::sself::root() {
-                          Ok(root) => root,
-                          Err(err) => abort!(USR_ILLEGAL_STATE, "failed to get root: {:?}", err),
+                      let root = match ::fvm_macro::__private::sdk::sself::root() {
+                          ::core::result::Result::Ok(root) => root,
+                          ::core::result::Result::Err(err) => {
+                              ::fvm_macro::abort!(USR_ILLEGAL_STATE, "failed to get root: {:?}", err)
+                          }
                       };
 
-                      // Load the actor state from the state tree.
-                      match Blockstore.get_cbor::<Self>(&root) {
-                          Ok(Some(state)) => state,
-                          Ok(None) => abort!(USR_ILLEGAL_STATE, "state does not exist"),
-                          Err(err) => abort!(USR_ILLEGAL_STATE, "failed to get state: {}", err),
-                            }
+                      // Load the actor state from the state tree, keeping its block to detect changes.
+                      let block = match ::fvm_macro::__private::sdk::ipld::get(&root) {
+                          ::core::result::Result::Ok(block) => block,
+                          ::core::result::Result::Err(err) => {
+                              ::fvm_macro::abort!(USR_ILLEGAL_STATE, "failed to get state: {:?}", err)
+                          }
+                      };
+                      #decode_state
+                      ::fvm_macro::__private::root::Rooted::loaded(state, root, block)
                 }
 
-                fn save(&self) -> Cid {
-                        let serialized = match to_vec(self) {
-                            Ok(s) => s,
-                            Err(err) => abort!(USR_SERIALIZATION, "failed to serialize state: {:?}", err),
+                fn save(
+                    state: &mut ::fvm_macro::__private::root::Rooted<Self>,
+                ) -> ::core::option::Option<::fvm_macro::__private::Cid> {
+                        #flush
+                        let serialized = match ::fvm_macro::__private::to_vec(#stored) {
+                            ::core::result::Result::Ok(s) => s,
+                            ::core::result::Result::Err(err) => {
+                                ::fvm_macro::abort!(USR_SERIALIZATION, "failed to serialize state: {:?}", err)
+                            }
                         };
-                        let cid = match sdk::ipld::put(Code::Blake2b256.into(), 32, DAG_CBOR, serialized.as_slice())
-                        {
-                            Ok(cid) => cid,
-                            Err(err) => abort!(USR_SERIALIZATION, "failed to store initial state: {:}", err),
+                        if state.is_unchanged(&serialized) {
+                            return ::core::option::Option::None;
+                        }
+                        let cid = match ::fvm_macro::__private::sdk::ipld::put(
+                            ::fvm_macro::__private::Code::Blake2b256.into(),
+                            32,
+                            ::fvm_macro::__private::DAG_CBOR,
+                            serialized.as_slice(),
+                        ) {
+                            ::core::result::Result::Ok(cid) => cid,
+                            ::core::result::Result::Err(err) => {
+                                ::fvm_macro::abort!(USR_SERIALIZATION, "failed to store initial state: {:}", err)
+                            }
                         };
-                        if let Err(err) = sdk::sself::set_root(&cid) {
-                            abort!(USR_ILLEGAL_STATE, "failed to set root ciid: {:}", err);
+                        if let ::core::result::Result::Err(err) = ::fvm_macro::__private::sdk::sself::set_root(&cid) {
+                            ::fvm_macro::abort!(USR_ILLEGAL_STATE, "failed to set root ciid: {:}", err);
                         }
-                        cid
+                        state.set_root(cid, serialized);
+                        ::core::option::Option::Some(cid)
                 }
             }
 
-            impl #ident {
+            impl #impl_generics #ident #ty_generics #where_clause {
                 pub fn invoke(id: u32) -> u32 {
-                    let _params = sdk::message::params_raw(id).unwrap().1;
-                    let _params = RawBytes::new(_params);
-                    let ret: Option<RawBytes> = match sdk::message::method_number() {
-                        #constructor_index =>#ident::constructor(),
-                        #( #item_impls1 )*
-                            _ => abort!(USR_UNHANDLED_MESSAGE, "unrecognized method"),
+                    let _params = ::fvm_macro::__private::sdk::message::params_raw(id).unwrap().1;
+                    let _params = ::fvm_macro::__private::RawBytes::new(_params);
+                    let ret: ::core::option::Option<::fvm_macro::__private::RawBytes> =
+                        match ::fvm_macro::__private::sdk::message::method_number() {
+                            #constructor_index => Self::constructor(_params),
+                            method => {
+                                let mut state = <Self as ::fvm_macro::__private::State>::load();
+                                let mutates = Self::__mutates(method);
+                                #immutable_snapshot
+                                let ret = Self::__dispatch(&mut state, method, _params);
+                                if mutates {
+                                    #immutable_check
+                                }
+                                // A state migrated on load is written back even by read-only messages.
+                                if mutates || state.is_stale() {
+                                    <Self as ::fvm_macro::__private::State>::save(&mut state);
+                                }
+                                ret
+                            }
                         };
 
                     match ret {
-                        None => NO_DATA_BLOCK_ID,
-                        Some(v) => match sdk::ipld::put_block(DAG_CBOR, v.bytes()) {
-                        Ok(id) => id,
-                            Err(err) => abort!(USR_SERIALIZATION, "failed to store return value: {}", err),
-                        },
+                        ::core::option::Option::None => ::fvm_macro::__private::NO_DATA_BLOCK_ID,
+                        ::core::option::Option::Some(v) => {
+                            match ::fvm_macro::__private::sdk::ipld::put_block(::fvm_macro::__private::DAG_CBOR, v.bytes()) {
+                                ::core::result::Result::Ok(id) => id,
+                                ::core::result::Result::Err(err) => {
+                                    ::fvm_macro::abort!(USR_SERIALIZATION, "failed to store return value: {}", err)
+                                }
+                            }
+                        }
                     }
                 }
 
 
-                pub fn constructor() -> Option<RawBytes> {
-                    use fvm_shared::ActorID;
-                    use fvm_sdk as sdk;
-
-                    const INIT_ACTOR_ADDR: ActorID = 1;
-                    if sdk::message::caller() != INIT_ACTOR_ADDR {
-                        abort!(USR_FORBIDDEN, "constructor invoked by non-init actor");
+                /// Runs the message with the given method number against the loaded state, without
+                /// saving it.
+                #[doc(hidden)]
+                #[allow(unused_variables)]
+                pub fn __dispatch(
+                    state: &mut ::fvm_macro::__private::root::Rooted<Self>,
+                    method: u64,
+                    _params: ::fvm_macro::__private::RawBytes,
+                ) -> ::core::option::Option<::fvm_macro::__private::RawBytes> {
+                    match method {
+                        #( #message_arms )*
+                        #( #component_arms )*
+                        #fallback_arm
                     }
+                }
 
-                    let state = <#ident>::default();
-                    state.save();
-                    None
+                /// Returns `true` if the message with the given method number may change the state.
+                #[doc(hidden)]
+                #[allow(unused_variables)]
+                pub fn __mutates(method: u64) -> bool {
+                    #mutates
                 }
 
+                #immutable
+
+                #decode
 
+                #constructor
+
+                #( #component_methods )*
             }
         )
     }
-}
 
-impl State<'_> {
-    fn generate_item_impl1(&self, item_impl: &item_impl::ItemImpl) -> TokenStream2 {
-        let impl_block = match item_impl.trait_path() {
-            Some(_) => Self::generate_trait_item_impl(item_impl),
-            None => Self::generate_inherent_item_impl1(self.contract, item_impl),
+    /// Generates the derives of the state, leaving out the traits it derives itself.
+    ///
+    /// Missing fields of a map encoded state fall back to the `Default` of the state, unknown
+    /// ones are ignored by serde. Tuple structs are encoded as arrays and enums are tagged with
+    /// their variant name; enums implement `Default` themselves if it is needed. `Clone`, `Debug`
+    /// and `Default` are not derived for a `no_derive` state.
+    fn generate_derives(&self, derive_default: bool) -> TokenStream2 {
+        let storage = self.contract.module().state();
+        let is_enum = matches!(storage.item(), StateItem::Enum(_));
+        let tuple_encoded = storage.has_named_fields() && storage.encoding() == Encoding::Tuple;
+        let serde = (!tuple_encoded).then(|| {
+            quote! {
+                ::fvm_macro::__private::serde::Serialize,
+                ::fvm_macro::__private::serde::Deserialize,
+            }
+        });
+        let mut derives = Vec::new();
+        if !storage.is_no_derive() {
+            derives.push(("Clone", quote! { ::core::clone::Clone }));
+            derives.push(("Debug", quote! { ::core::fmt::Debug }));
+            if derive_default && !is_enum {
+                derives.push(("Default", quote! { ::core::default::Default }));
+            }
+        }
+        let derives = derives
+            .into_iter()
+            .filter(|(name, _)| !storage.derives(name))
+            .map(|(_, path)| path)
+            .collect::<Vec<_>>();
+        if serde.is_none() && derives.is_empty() {
+            return quote! {};
+        }
+        let serde_attr = match storage.encoding() {
+            _ if tuple_encoded => None,
+            Encoding::Map => Some(quote! { #[serde(crate = "::fvm_macro::__private::serde", default)] }),
+            Encoding::Tuple => Some(quote! { #[serde(crate = "::fvm_macro::__private::serde")] }),
         };
-
         quote! {
-            #impl_block
+            #[derive( #serde #( #derives ),* )]
+            #serde_attr
         }
     }
 
-    fn generate_inherent_item_impl1(contract: &contract::Contract, item_impl: &item_impl::ItemImpl) -> TokenStream2 {
-        assert!(item_impl.trait_path().is_none());
-        let messages = item_impl
-            .iter_messages()
-            .enumerate()
-            .map(|(index, cws)| Self::generate_inherent_message1(index as u64, contract, cws.callable()));
+    /// Generates the serde impls of a tuple encoded state with named fields, leaving out the
+    /// transient fields.
+    ///
+    /// Returns `None` if the state derives its serde impls.
+    fn generate_tuple_impls(&self) -> Option<TokenStream2> {
+        let storage = self.contract.module().state();
+        if !storage.has_named_fields() || storage.encoding() != Encoding::Tuple {
+            return None;
+        }
+        let component_fields = components::parsed_fields(self.contract);
+        let (skipped, persisted): (Vec<_>, Vec<_>) = storage
+            .fields()
+            .chain(component_fields.iter())
+            .partition(|field| storage.is_transient(field));
+        Some(tuple::generate_serde_impls(storage.ident(), storage.generics(), &persisted, &skipped))
+    }
+
+    /// Generates the generics of the impls of the state.
+    ///
+    /// The impls of a generic state require the state to be serializable, and to implement
+    /// `Default` if the generated constructor uses it, since the derived impls only exist for
+    /// some type arguments. They also carry over the bounds declared on the state.
+    fn generate_impl_generics(&self) -> (TokenStream2, TokenStream2, TokenStream2) {
+        let storage = self.contract.module().state();
+        let ident = storage.ident();
+        let (impl_generics, ty_generics, where_clause) = storage.generics().split_for_impl();
+        if storage.generics().params.is_empty() {
+            return (quote! {}, quote! {}, quote! {});
+        }
+        let default = self.contract.module().constructor().is_none().then_some(quote! { + ::core::default::Default });
+        let immutable_types = storage.immutable_fields().map(|field| &field.ty);
+        let predicates = where_clause.map(|where_clause| &where_clause.predicates).into_iter().flatten();
+        (
+            quote! { #impl_generics },
+            quote! { #ty_generics },
+            quote! {
+                where
+                    #ident #ty_generics: ::fvm_macro::__private::serde::Serialize
+                        + ::fvm_macro::__private::serde::de::DeserializeOwned #default,
+                    #( #immutable_types: ::core::clone::Clone + ::core::cmp::PartialEq, )*
+                    #( #predicates, )*
+            },
+        )
+    }
+
+    /// Generates `impl Default` for a state with `default = expr` fields, initialising the other
+    /// fields with `Default::default()`.
+    ///
+    /// Returns `None` if no field has an initial value, in which case `Default` is derived.
+    fn generate_default_impl(&self) -> Option<TokenStream2> {
+        let storage = self.contract.module().state();
+        if !storage.has_default_values() {
+            return None;
+        }
+        let ident = storage.ident();
+        let component_fields = components::parsed_fields(self.contract);
+        let fields = storage.fields().chain(component_fields.iter()).map(|field| {
+            let name = &field.ident;
+            match storage.default_value(field) {
+                Some(expr) => quote! { #name: #expr },
+                None => quote! { #name: ::core::default::Default::default() },
+            }
+        });
+        // The fields without an initial value of a generic state only implement `Default` for
+        // some type arguments.
+        let (impl_generics, ty_generics, where_clause) = storage.generics().split_for_impl();
+        let default_types = storage
+            .fields()
+            .filter(|field| storage.default_value(field).is_none())
+            .map(|field| &field.ty)
+            .filter(|_| !storage.generics().params.is_empty());
+        let predicates = where_clause.map(|where_clause| &where_clause.predicates).into_iter().flatten();
+        Some(quote! {
+            impl #impl_generics ::core::default::Default for #ident #ty_generics
+            where
+                #( #default_types: ::core::default::Default, )*
+                #( #predicates, )*
+            {
+                fn default() -> Self {
+                    Self {
+                        #( #fields, )*
+                    }
+                }
+            }
+        })
+    }
+
+    /// Generates `__immutable`, copying the `immutable` fields so that the dispatcher can check
+    /// that a message left them unchanged.
+    ///
+    /// Returns `None` if the state has no immutable fields.
+    fn generate_immutable(&self) -> Option<TokenStream2> {
+        let storage = self.contract.module().state();
+        let (fields, types): (Vec<_>, Vec<_>) = storage
+            .immutable_fields()
+            .map(|field| (&field.ident, &field.ty))
+            .unzip();
+        if fields.is_empty() {
+            return None;
+        }
+        Some(quote! {
+            /// Returns a copy of the immutable fields.
+            #[doc(hidden)]
+            pub fn __immutable(&self) -> ( #( #types, )* ) {
+                ( #( ::core::clone::Clone::clone(&self.#fields), )* )
+            }
+        })
+    }
+
+    /// Generates `__decode` for a versioned state, decoding the state root and running the
+    /// migrations from its version up to the current one.
+    ///
+    /// Returns `None` if the state is not versioned.
+    fn generate_decode(&self) -> Option<TokenStream2> {
+        let version = self.contract.module().state().version()?;
+        let migrations = self.contract.module().migrations();
+        let arms = migrations.iter().enumerate().map(|(index, migration)| {
+            let input = migration.input();
+            let chain = migrations[index..].iter().map(|migration| migration.ident());
+            let (pattern, decode) = match migration.from() {
+                // Version 0 is the layout from before the state was versioned.
+                0 => (
+                    quote! { ::core::option::Option::None },
+                    quote! { ::fvm_macro::__private::version::decode_unversioned::<#input>(block) },
+                ),
+                from => (
+                    quote! { ::core::option::Option::Some(#from) },
+                    quote! { ::fvm_macro::__private::version::decode::<#input>(block) },
+                ),
+            };
+            quote! {
+                #pattern => {
+                    let state = #decode;
+                    #( let state = Self::#chain(state); )*
+                    (state, true)
+                }
+            }
+        });
+        Some(quote! {
+            /// Decodes the state root, migrating it if it was written at an older version, and
+            /// returns whether it was migrated.
+            #[doc(hidden)]
+            pub fn __decode(block: &[u8]) -> (Self, bool) {
+                match ::fvm_macro::__private::version::of(block) {
+                    ::core::option::Option::Some(#version) => {
+                        (::fvm_macro::__private::version::decode::<Self>(block), false)
+                    }
+                    #( #arms )*
+                    other => ::fvm_macro::__private::version::unsupported(other),
+                }
+            }
+        })
+    }
+
+    fn generate_component_method(message: &components::ComponentMessage) -> TokenStream2 {
+        let ident = &message.ident;
+        let receiver = if message.rooted {
+            quote! { state: &mut ::fvm_macro::__private::root::Rooted<Self> }
+        } else if message.mutates {
+            quote! { &mut self }
+        } else {
+            quote! { &self }
+        };
+        let params = if message.inputs.is_empty() {
+            format_ident!("_params")
+        } else {
+            format_ident!("params")
+        };
+        let body = &message.body;
         quote! {
-                #( #messages )*
+            pub fn #ident(
+                #receiver,
+                #params: ::fvm_macro::__private::RawBytes,
+            ) -> ::core::option::Option<::fvm_macro::__private::RawBytes> {
+                #body
             }
+        }
     }
 
-    fn generate_trait_message(message: &message::Message) -> TokenStream2 {
-        let span = message.span();
-        let attrs = message.attrs();
-        let vis = message.visibility();
-        let receiver = message.receiver();
-        let ident = message.ident();
-        let output_ident =
-            format_ident!("{}Output", ident.to_string().to_lower_camel_case());
-        let inputs = message.inputs();
-        let output = message
-            .output()
-            .cloned()
-            .unwrap_or_else(|| syn::parse_quote! { () });
-        let statements = message.statements();
-        quote_spanned!(span =>
-            type #output_ident = #output;
-            #( #attrs )*
-            #vis fn #ident(#receiver #( , #inputs )* ) -> Self::#output_ident {
-                #( #statements )*
+    fn generate_component_arm(message: &components::ComponentMessage) -> TokenStream2 {
+        let ident = &message.ident;
+        let method_number = message.method_number();
+        if message.rooted {
+            return quote! { #method_number => Self::#ident(state, _params), };
+        }
+        quote! { #method_number => state.#ident(_params), }
+    }
+
+    /// Generates the body of `__mutates`, matching the method numbers of the messages taking
+    /// `&mut self`.
+    fn generate_mutates(&self) -> TokenStream2 {
+        let module = self.contract.module();
+        let messages = module
+            .dispatched_messages()
+            .into_iter()
+            .filter(|(_, cws)| Self::message_mutates(cws.callable()))
+            .map(|(method_number, _)| method_number);
+        let components = components::messages(self.contract)
+            .into_iter()
+            .filter(|message| message.mutates)
+            .map(|message| message.method_number());
+        let method_numbers = messages
+            .chain(components)
+            .collect::<Vec<_>>();
+        let fallback = module
+            .fallback()
+            .is_some_and(|cws| Self::message_mutates(cws.callable()));
+        if method_numbers.is_empty() {
+            return quote! { #fallback };
+        }
+        quote! {
+            match method {
+                #( #method_numbers )|* => true,
+                _ => #fallback,
             }
-        )
+        }
     }
 
+    fn message_mutates(message: &message::Message) -> bool {
+        message.receiver().is_ref_mut()
+    }
 
-    fn generate_trait_item_impl(item_impl: &item_impl::ItemImpl) -> TokenStream2 {
-        assert!(item_impl.trait_path().is_some());
-        let span = item_impl.span();
-        let attrs = item_impl.attrs();
-        let messages = item_impl
-            .iter_messages()
-            .map(|cws| Self::generate_trait_message(cws.callable()));
-        let trait_path = item_impl
-            .trait_path()
-            .expect("encountered missing trait path for trait impl block");
-        let self_type = item_impl.self_type();
-        quote_spanned!(span =>
-            #( #attrs )*
-            impl #trait_path for #self_type {
-                #( #messages )*
+    fn generate_constructor(&self) -> TokenStream2 {
+        let constructor = self.contract.module().constructor();
+        let caller_policy = constructor
+            .as_ref()
+            .map(|cws| cws.callable().caller_policy().clone())
+            .unwrap_or_default();
+        let caller_check = match caller_policy {
+            CallerPolicy::Init => quote! {
+                const INIT_ACTOR_ADDR: ::fvm_macro::__private::fvm_shared::ActorID = 1;
+                if ::fvm_macro::__private::sdk::message::caller() != INIT_ACTOR_ADDR {
+                    ::fvm_macro::abort!(USR_FORBIDDEN, "constructor invoked by non-init actor");
+                }
+            },
+            CallerPolicy::ActorId(actor_id) => quote! {
+                const CONSTRUCTOR_CALLER: ::fvm_macro::__private::fvm_shared::ActorID = #actor_id;
+                if ::fvm_macro::__private::sdk::message::caller() != CONSTRUCTOR_CALLER {
+                    ::fvm_macro::abort!(USR_FORBIDDEN, "constructor invoked by unexpected actor");
+                }
+            },
+            CallerPolicy::Address(address) => quote! {
+                let expected = match <::fvm_macro::__private::fvm_shared::address::Address as ::core::str::FromStr>::from_str(#address) {
+                    ::core::result::Result::Ok(expected) => expected,
+                    ::core::result::Result::Err(err) => {
+                        ::fvm_macro::abort!(USR_ILLEGAL_STATE, "invalid constructor caller address: {:?}", err)
+                    }
+                };
+                let caller = ::fvm_macro::__private::sdk::message::caller();
+                if ::fvm_macro::__private::sdk::actor::resolve_address(&expected) != ::core::option::Option::Some(caller) {
+                    ::fvm_macro::abort!(USR_FORBIDDEN, "constructor invoked by unexpected actor");
+                }
+            },
+            CallerPolicy::Any => quote! {},
+        };
+        let (state, params) = match constructor {
+            Some(cws) => {
+                let ident = cws.ident();
+                if cws.inputs().next().is_none() {
+                    (quote! { Self::#ident() }, quote! { ::core::option::Option::Some(&_params) })
+                } else {
+                    (quote! { Self::#ident(_params) }, quote! { ::core::option::Option::None })
+                }
             }
-        )
+            None => (
+                quote! { <Self as ::core::default::Default>::default() },
+                quote! { ::core::option::Option::Some(&_params) },
+            ),
+        };
+        let component_init = components::constructor_init(self.contract, params);
+        quote! {
+            pub fn constructor(
+                _params: ::fvm_macro::__private::RawBytes,
+            ) -> ::core::option::Option<::fvm_macro::__private::RawBytes> {
+                #caller_check
+
+                let mut state = ::fvm_macro::__private::root::Rooted::new(#state);
+                #component_init
+                <Self as ::fvm_macro::__private::State>::save(&mut state);
+                ::core::option::Option::None
+            }
+        }
     }
+}
 
-    fn generate_inherent_message1(index: u64, contract: &contract::Contract, message: &message::Message) -> TokenStream2 {
+impl State<'_> {
+    /// Generates the dispatcher arm of the message for the method number `pattern`.
+    ///
+    /// The fallback message is called with the method number of the invocation in front of the
+    /// raw params. The method number is passed explicitly since it differs from the one of the
+    /// invocation for the steps of a batch.
+    fn generate_inherent_message1(pattern: TokenStream2, contract: &contract::Contract, message: &message::Message) -> TokenStream2 {
         let ident = message.ident();
-        let inputs = message.inputs();
-        let state_ident = contract.module().state().ident();
-        let index = index + 2;
-        if inputs.len() == 1 {
-            quote! { #index => <#state_ident>::load().#ident(), }
-        } else {
-            quote! { #index => <#state_ident>::load().#ident(_params), }
+        let access_checks = Self::generate_access_checks(contract, message);
+        let env = message.has_env().then(|| {
+            quote! { &::fvm_macro::__private::Env { method_number: method, ..::fvm_macro::__private::Env::from_syscalls() } }
+        });
+        let method_number = message.has_wildcard_selector().then_some(quote! { method });
+        let params = message.params().next().map(|_| quote! { _params });
+        let args = env.into_iter().chain(method_number).chain(params);
+        let args = quote! { #( #args ),* };
+        let nonreentrant = message.is_nonreentrant();
+        if access_checks.is_empty() && !nonreentrant {
+            return quote! { #pattern => state.#ident(#args), };
+        }
+        // The lock is saved before the body of a mutating message runs so that nested invocations
+        // observe it. Read-only messages never save the state, so they only check the lock.
+        let mutates = message.receiver().is_ref_mut();
+        let enter = nonreentrant.then(|| {
+            if mutates {
+                quote! {
+                    ::fvm_macro::__private::reentrancy::enter(&mut state.reentrancy_lock);
+                    <Self as ::fvm_macro::__private::State>::save(state);
+                }
+            } else {
+                quote! {
+                    ::fvm_macro::__private::reentrancy::ensure_unlocked(state.reentrancy_lock);
+                }
+            }
+        });
+        let exit = (nonreentrant && mutates).then(|| {
+            quote! {
+                ::fvm_macro::__private::reentrancy::exit(&mut state.reentrancy_lock);
+            }
+        });
+        quote! {
+            #pattern => {
+                #access_checks
+                #enter
+                let ret = state.#ident(#args);
+                #exit
+                ret
+            }
+        }
+    }
+
+    /// Generates the `when_not_paused` check and the caller checks of the `only` and `roles`
+    /// access rules of the message.
+    ///
+    /// Returns an empty token stream if the message has no such rules.
+    fn generate_access_checks(contract: &contract::Contract, message: &message::Message) -> TokenStream2 {
+        let when_not_paused = message.is_when_not_paused().then(|| {
+            quote! {
+                ::fvm_macro::__private::pausable::ensure_not_paused(state.paused);
+            }
+        });
+        let only = message.only().map(|field| {
+            let error = format!("caller is not the {} of the contract", field);
+            quote! {
+                if !::fvm_macro::__private::Principal::is_caller(&state.#field, caller) {
+                    ::fvm_macro::abort!(USR_FORBIDDEN, #error);
+                }
+            }
+        });
+        let roles = (!message.roles().is_empty()).then(|| {
+            let roles = message.roles();
+            let error = format!(
+                "caller is not a member of {}",
+                roles.iter().map(ToString::to_string).collect::<Vec<_>>().join(" or "),
+            );
+            let is_member = roles.iter().map(|role| {
+                if contract.config().rbac() {
+                    let role = role.to_string();
+                    quote! { state.rbac.has_role(#role, caller) }
+                } else {
+                    quote! { ::fvm_macro::__private::Members::contains_caller(&state.#role, caller) }
+                }
+            });
+            quote! {
+                if !( #( #is_member )||* ) {
+                    ::fvm_macro::abort!(USR_FORBIDDEN, #error);
+                }
+            }
+        });
+        if only.is_none() && roles.is_none() {
+            return quote! { #when_not_paused };
+        }
+        quote! {
+            #when_not_paused
+            let caller = ::fvm_macro::__private::sdk::message::caller();
+            #only
+            #roles
         }
     }
 }
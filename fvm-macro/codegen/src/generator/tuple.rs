@@ -0,0 +1,150 @@
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{
+    format_ident,
+    quote,
+};
+
+/// Generates tuple encoded `Serialize` and `Deserialize` impls for a struct with named fields,
+/// encoding the same CBOR array as `Serialize_tuple` and `Deserialize_tuple`.
+///
+/// The derives of `serde_tuple` expand to bare `serde` and `serde_tuple` paths and do not bound
+/// the type parameters, so the generated structs serialize through these impls instead. Like
+/// `serde_tuple`, they go through a tuple struct mirroring the fields, which derives the serde
+/// impls with the `#[serde(...)]` attributes of the fields and refers to serde through
+/// `::fvm_macro::__private`. The impls are bounded by the types of the fields without serde
+/// attributes. The `skipped` fields are left out and initialised with `Default` when
+/// deserializing.
+pub fn generate_serde_impls(
+    ident: &syn::Ident,
+    generics: &syn::Generics,
+    persisted: &[&syn::Field],
+    skipped: &[&syn::Field],
+) -> TokenStream2 {
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    let params = &generics.params;
+    let predicates = where_clause
+        .map(|where_clause| where_clause.predicates.iter().collect::<Vec<_>>())
+        .unwrap_or_default();
+    let names = persisted.iter().map(|field| &field.ident).collect::<Vec<_>>();
+    let types = persisted.iter().map(|field| &field.ty).collect::<Vec<_>>();
+    let serde_attrs = persisted
+        .iter()
+        .map(|field| serde_attributes(field).collect::<Vec<_>>())
+        .collect::<Vec<_>>();
+    // The decoded elements are bound to generated names, which cannot shadow anything.
+    let locals = (0..persisted.len())
+        .map(|index| format_ident!("__field{}", index))
+        .collect::<Vec<_>>();
+    let bounded_types = persisted
+        .iter()
+        .filter(|field| serde_attributes(field).next().is_none())
+        .map(|field| &field.ty)
+        .collect::<Vec<_>>();
+    let serialize_bound = bound(&bounded_types, quote! { ::fvm_macro::__private::serde::Serialize });
+    let deserialize_bound = bound(&bounded_types, quote! { ::fvm_macro::__private::serde::Deserialize<'de> });
+    let skipped_names = skipped.iter().map(|field| &field.ident);
+    let skipped_types = skipped.iter().map(|field| &field.ty);
+    let serialize_marker = marker(generics, Some(&syn::parse_quote! { '__field }));
+    let deserialize_marker = marker(generics, None);
+    let mut de_generics = generics.clone();
+    de_generics.params.insert(0, syn::parse_quote! { 'de });
+    let (de_impl_generics, _, _) = de_generics.split_for_impl();
+    let (serialize, deserialize) = if persisted.is_empty() {
+        (
+            quote! {
+                ::fvm_macro::__private::serde::ser::SerializeTuple::end(
+                    ::fvm_macro::__private::serde::Serializer::serialize_tuple(serializer, 0)?,
+                )
+            },
+            quote! {
+                <[(); 0] as ::fvm_macro::__private::serde::Deserialize>::deserialize(deserializer)?;
+            },
+        )
+    } else {
+        (
+            quote! {
+                #[derive(::fvm_macro::__private::serde::Serialize)]
+                #[serde(crate = "::fvm_macro::__private::serde", bound = #serialize_bound)]
+                struct __Tuple<'__field, #params>(
+                    #( #( #serde_attrs )* &'__field #types, )*
+                    #serialize_marker
+                ) #where_clause;
+
+                ::fvm_macro::__private::serde::Serialize::serialize(
+                    &__Tuple( #( &self.#names, )* ::core::marker::PhantomData ),
+                    serializer,
+                )
+            },
+            quote! {
+                #[derive(::fvm_macro::__private::serde::Deserialize)]
+                #[serde(crate = "::fvm_macro::__private::serde", bound = #deserialize_bound)]
+                struct __Tuple<#params>(
+                    #( #( #serde_attrs )* #types, )*
+                    #deserialize_marker
+                ) #where_clause;
+
+                let __Tuple( #( #locals, )* _ ) =
+                    <__Tuple #ty_generics as ::fvm_macro::__private::serde::Deserialize>::deserialize(deserializer)?;
+            },
+        )
+    };
+    quote! {
+        impl #impl_generics ::fvm_macro::__private::serde::Serialize for #ident #ty_generics
+        where
+            #( #bounded_types: ::fvm_macro::__private::serde::Serialize, )*
+            #( #predicates, )*
+        {
+            fn serialize<__S>(&self, serializer: __S) -> ::core::result::Result<__S::Ok, __S::Error>
+            where
+                __S: ::fvm_macro::__private::serde::Serializer,
+            {
+                #serialize
+            }
+        }
+
+        impl #de_impl_generics ::fvm_macro::__private::serde::Deserialize<'de> for #ident #ty_generics
+        where
+            #( #bounded_types: ::fvm_macro::__private::serde::Deserialize<'de>, )*
+            #( #skipped_types: ::core::default::Default, )*
+            #( #predicates, )*
+        {
+            fn deserialize<__D>(deserializer: __D) -> ::core::result::Result<Self, __D::Error>
+            where
+                __D: ::fvm_macro::__private::serde::Deserializer<'de>,
+            {
+                #deserialize
+                ::core::result::Result::Ok(Self {
+                    #( #names: #locals, )*
+                    #( #skipped_names: ::core::default::Default::default(), )*
+                })
+            }
+        }
+    }
+}
+
+/// Returns the `#[serde(...)]` attributes of the field.
+fn serde_attributes(field: &syn::Field) -> impl Iterator<Item = &syn::Attribute> {
+    field.attrs.iter().filter(|attr| attr.path.is_ident("serde"))
+}
+
+/// Returns the `field: Trait` predicates of a `#[serde(bound = "...")]` attribute.
+fn bound(types: &[&syn::Type], bound: TokenStream2) -> String {
+    types
+        .iter()
+        .map(|ty| quote! { #ty: #bound }.to_string())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Returns the skipped last field of a mirror, which uses every generic parameter and keeps a
+/// mirror of a single field from being serialized as a newtype.
+fn marker(generics: &syn::Generics, lifetime: Option<&syn::Lifetime>) -> TokenStream2 {
+    let lifetimes = lifetime
+        .into_iter()
+        .chain(generics.lifetimes().map(|param| &param.lifetime));
+    let types = generics.type_params().map(|param| &param.ident);
+    quote! {
+        #[serde(skip)]
+        ::core::marker::PhantomData<( #( &#lifetimes (), )* #( fn() -> #types, )* )>,
+    }
+}
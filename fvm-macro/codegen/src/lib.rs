@@ -16,6 +16,18 @@ impl<'a> CodeGenerator for &'a contract::Contract {
     type Generator = gen_contract::Contract<'a>;
 }
 
+impl<'a> CodeGenerator for &'a fvm_ir::ir::blake2::Blake2x256Macro {
+    type Generator = generator::blake2b::Blake2x256<'a>;
+}
+
+impl<'a> CodeGenerator for &'a fvm_ir::ir::selector::SelectorMacro<fvm_ir::ir::selector::SelectorId> {
+    type Generator = generator::selector::SelectorId<'a>;
+}
+
+impl<'a> CodeGenerator for &'a fvm_ir::ir::selector::SelectorMacro<fvm_ir::ir::selector::SelectorBytes> {
+    type Generator = generator::selector::SelectorBytes<'a>;
+}
+
 
 pub fn generate_code<T>(entity: T) -> TokenStream2
     where
@@ -0,0 +1,15 @@
+use fvm_codegen::generate_code;
+use fvm_ir::ir::blake2::Blake2x256Macro;
+use proc_macro2::TokenStream as TokenStream2;
+
+pub fn generate(input: TokenStream2) -> TokenStream2 {
+    match generate_or_err(input) {
+        Ok(tokens) => tokens,
+        Err(err) => err.to_compile_error(),
+    }
+}
+
+pub fn generate_or_err(input: TokenStream2) -> Result<TokenStream2, syn::Error> {
+    let hash = Blake2x256Macro::try_from(input)?;
+    Ok(generate_code(&hash))
+}
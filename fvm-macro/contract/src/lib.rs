@@ -1,3 +1,6 @@
+mod blake2b;
+mod selector;
+
 use fvm_ir::ir::contract::Contract;
 use fvm_codegen::generate_code;
 
@@ -6,9 +9,47 @@ pub fn contract(attr: proc_macro::TokenStream, item: proc_macro::TokenStream) ->
     generate(attr.into(), item.into())
 }
 
+/// Computes the BLAKE2b-256 hash of a string or byte string literal at compile time, as a
+/// `[u8; 32]` array.
+///
+/// ```ignore
+/// const HASH: [u8; 32] = fvm_macro::blake2x256!("Minted");
+/// ```
+#[proc_macro]
+pub fn blake2x256(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    blake2b::generate(input.into()).into()
+}
+
+/// Computes the selector of a string or byte string literal at compile time, as a `u32`: the
+/// first four bytes of its BLAKE2b-256 hash, big endian.
+///
+/// The generated messages of components are numbered by the selector of `<component>::<message>`:
+///
+/// ```ignore
+/// const TRANSFER_OWNERSHIP: u64 = fvm_macro::selector_id!("Ownable::transfer_ownership") as u64;
+/// ```
+#[proc_macro]
+pub fn selector_id(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    selector::generate_selector_id(input.into()).into()
+}
+
+/// Computes the selector of a string or byte string literal at compile time, as the `[u8; 4]`
+/// array of the first four bytes of its BLAKE2b-256 hash.
+///
+/// ```ignore
+/// const SELECTOR: [u8; 4] = fvm_macro::selector_bytes!("Ownable::owner");
+/// ```
+#[proc_macro]
+pub fn selector_bytes(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    selector::generate_selector_bytes(input.into()).into()
+}
+
 
-fn generate(_: proc_macro2::TokenStream, input: proc_macro2::TokenStream) -> proc_macro::TokenStream {
-    let contract = Contract::new(input.clone()).unwrap();
+fn generate(attr: proc_macro2::TokenStream, input: proc_macro2::TokenStream) -> proc_macro::TokenStream {
+    let contract = match Contract::new(attr, input) {
+        Ok(contract) => contract,
+        Err(err) => return err.to_compile_error().into(),
+    };
     let generator_code = generate_code(&contract);
-    return generator_code.into();
+    generator_code.into()
 }
\ No newline at end of file
@@ -2,9 +2,10 @@ use crate::{
     format_err_spanned,
     format_err,
 };
-use proc_macro2::{Ident, Span};
+use proc_macro2::{Group, Ident, Literal, Span, TokenStream as TokenStream2, TokenTree};
 use syn::spanned::Spanned;
-use crate::ir::{attrs, selector};
+use crate::ir::{attrs, blake2::blake2b_32, selector};
+use crate::error::ExtError as _;
 
 
 pub trait Attrs {
@@ -128,7 +129,7 @@ impl FvmAttribute {
             .any(|arg| matches!(arg.kind(), AttributeArg::Anonymous))
     }
 
-    pub fn args(&self) -> ::core::slice::Iter<AttributeFrag> {
+    pub fn args(&self) -> ::core::slice::Iter<'_, AttributeFrag> {
         self.args.iter()
     }
 
@@ -154,6 +155,110 @@ impl FvmAttribute {
         self.args()
             .any(|arg| matches!(arg.kind(), AttributeArg::Payable))
     }
+
+    pub fn is_ownable(&self) -> bool {
+        self.args()
+            .any(|arg| matches!(arg.kind(), AttributeArg::Ownable))
+    }
+
+    pub fn is_pausable(&self) -> bool {
+        self.args()
+            .any(|arg| matches!(arg.kind(), AttributeArg::Pausable))
+    }
+
+    pub fn is_no_derive(&self) -> bool {
+        self.args()
+            .any(|arg| matches!(arg.kind(), AttributeArg::NoDerive))
+    }
+
+    pub fn is_when_not_paused(&self) -> bool {
+        self.args()
+            .any(|arg| matches!(arg.kind(), AttributeArg::WhenNotPaused))
+    }
+
+    pub fn is_nonreentrant(&self) -> bool {
+        self.args()
+            .any(|arg| matches!(arg.kind(), AttributeArg::Nonreentrant))
+    }
+
+    pub fn is_transient(&self) -> bool {
+        self.args()
+            .any(|arg| matches!(arg.kind(), AttributeArg::Transient))
+    }
+
+    pub fn is_immutable(&self) -> bool {
+        self.args()
+            .any(|arg| matches!(arg.kind(), AttributeArg::Immutable))
+    }
+
+    /// Returns the initial value of a field given with `default = expr`, spanned to the
+    /// attribute argument.
+    pub fn default_value(&self) -> Option<syn::Expr> {
+        self.args().find_map(|arg| {
+            if let attrs::AttributeArg::Default(value) = arg.kind() {
+                return Some(value.to_expr(arg.span()));
+            }
+            None
+        })
+    }
+
+    pub fn version(&self) -> Option<u64> {
+        self.args().find_map(|arg| {
+            if let attrs::AttributeArg::Version(version) = arg.kind() {
+                return Some(*version);
+            }
+            None
+        })
+    }
+
+    pub fn encoding(&self) -> Option<Encoding> {
+        self.args().find_map(|arg| {
+            if let attrs::AttributeArg::Encoding(encoding) = arg.kind() {
+                return Some(*encoding);
+            }
+            None
+        })
+    }
+
+    /// Returns the state version a `migrate(from = N)` function upgrades from.
+    pub fn migrate_from(&self) -> Option<u64> {
+        self.args().find_map(|arg| {
+            if let attrs::AttributeArg::Migrate(from) = arg.kind() {
+                return Some(*from);
+            }
+            None
+        })
+    }
+
+    pub fn only(&self) -> Option<Ident> {
+        self.args().find_map(|arg| {
+            if let attrs::AttributeArg::Only(field) = arg.kind() {
+                return Some(field.clone());
+            }
+            None
+        })
+    }
+
+    pub fn roles(&self) -> Vec<Ident> {
+        self.args()
+            .filter_map(|arg| {
+                if let attrs::AttributeArg::Roles(roles) = arg.kind() {
+                    return Some(roles.clone());
+                }
+                None
+            })
+            .flatten()
+            .collect()
+    }
+
+    pub fn caller_policy(&self) -> Option<CallerPolicy> {
+        self.args().find_map(|arg| {
+            if let attrs::AttributeArg::Caller(policy) = arg.kind() {
+                return Some(policy.clone());
+            }
+            None
+        })
+    }
 }
 
 
@@ -172,15 +277,19 @@ impl TryFrom<syn::Attribute> for FvmAttribute {
         }
 
 
+        let attr = syn::Attribute {
+            tokens: quote_default_value(quote_wildcard_selector(attr.tokens.clone())),
+            ..attr
+        };
         match attr.parse_meta().map_err(|_| {
             format_err_spanned!(attr, "unexpected fvm attribute structure")
-        }).unwrap() {
+        })? {
             syn::Meta::List(meta_list) => {
                 let args = meta_list
                     .nested
                     .into_iter()
                     .map(<AttributeFrag as TryFrom<_>>::try_from)
-                    .collect::<Result<Vec<_>, syn::Error>>().unwrap();
+                    .collect::<Result<Vec<_>, syn::Error>>()?;
 
                 if args.is_empty() {
                     return Err(format_err_spanned!(
@@ -225,11 +334,25 @@ pub enum AttributeArg {
     Topic,
     Message,
     Constructor,
+    Receive,
     Payable,
     Implementation,
     Selector(SelectorOrWildcard),
     Namespace(Namespace),
-
+    Caller(CallerPolicy),
+    Only(Ident),
+    Roles(Vec<Ident>),
+    Ownable,
+    Pausable,
+    WhenNotPaused,
+    Nonreentrant,
+    Transient,
+    Immutable,
+    Default(DefaultValue),
+    Version(u64),
+    Migrate(u64),
+    Encoding(Encoding),
+    NoDerive,
 }
 
 
@@ -240,12 +363,39 @@ impl TryFrom<syn::NestedMeta> for AttributeFrag {
         match nested_meta {
             syn::NestedMeta::Meta(meta) => {
                 match &meta {
-                    //TODO:syn::Meta::NameValue
-                    syn::Meta::NameValue(_) => {
-                        Err(format_err_spanned!(
-                            meta,
-                            "unknown fvm attribute argument (list)"
-                        ))
+                    syn::Meta::NameValue(name_value) => {
+                        name_value
+                            .path
+                            .get_ident()
+                            .map(Ident::to_string)
+                            .ok_or_else(|| format_err_spanned!(meta, "unknown fvm attribute (name = value)"))
+                            .and_then(|ident| match ident.as_str() {
+                                "caller" => CallerPolicy::try_from(&name_value.lit)
+                                    .map(AttributeArg::Caller),
+                                "only" => parse_idents(&name_value.lit)
+                                    .and_then(|idents| match <[Ident; 1]>::try_from(idents) {
+                                        Ok([ident]) => Ok(AttributeArg::Only(ident)),
+                                        Err(_) => Err(format_err_spanned!(
+                                            name_value.lit,
+                                            "expected a single state field name for `only`"
+                                        )),
+                                    }),
+                                "roles" => parse_idents(&name_value.lit).map(AttributeArg::Roles),
+                                "selector" => SelectorOrWildcard::try_from(&name_value.lit)
+                                    .map(AttributeArg::Selector),
+                                "default" => DefaultValue::try_from(&name_value.lit)
+                                    .map(AttributeArg::Default),
+                                "version" => parse_state_version(&name_value.lit)
+                                    .map(AttributeArg::Version),
+                                "encoding" => Encoding::try_from(&name_value.lit)
+                                    .map(AttributeArg::Encoding),
+                                "namespace" => Namespace::try_from(&name_value.lit)
+                                    .map(AttributeArg::Namespace),
+                                _ => Err(format_err_spanned!(
+                                    meta, "unknown fvm attribute argument (name = value)"
+                                ))
+                            })
+                            .map(|kind| AttributeFrag { ast: meta.clone(), arg: kind })
                     }
                     syn::Meta::Path(path) => {
                         path
@@ -257,17 +407,29 @@ impl TryFrom<syn::NestedMeta> for AttributeFrag {
                                 "state" => Ok(AttributeArg::State),
                                 "message" => Ok(AttributeArg::Message),
                                 "constructor" => Ok(AttributeArg::Constructor),
+                                "receive" => Ok(AttributeArg::Receive),
                                 "event" => Ok(AttributeArg::Event),
                                 "anonymous" => Ok(AttributeArg::Anonymous),
                                 "topic" => Ok(AttributeArg::Topic),
                                 "payable" => Ok(AttributeArg::Payable),
                                 "impl" => Ok(AttributeArg::Implementation),
+                                "ownable" => Ok(AttributeArg::Ownable),
+                                "pausable" => Ok(AttributeArg::Pausable),
+                                "no_derive" => Ok(AttributeArg::NoDerive),
+                                "when_not_paused" => Ok(AttributeArg::WhenNotPaused),
+                                "nonreentrant" => Ok(AttributeArg::Nonreentrant),
+                                "transient" => Ok(AttributeArg::Transient),
+                                "immutable" => Ok(AttributeArg::Immutable),
                                 _ => Err(format_err_spanned!(
                                     meta, "unknown state attribute (path)"
                                 ))
                             })
                             .map(|kind| AttributeFrag { ast: meta, arg: kind })
                     }
+                    syn::Meta::List(meta_list) if meta_list.path.is_ident("migrate") => {
+                        parse_migrate(meta_list)
+                            .map(|from| AttributeFrag { ast: meta.clone(), arg: AttributeArg::Migrate(from) })
+                    }
                     syn::Meta::List(_) => {
                         Err(format_err_spanned!(
                             meta,
@@ -295,10 +457,25 @@ impl AttributeArg {
             Self::Topic => AttributeArgKind::Topic,
             Self::Message => AttributeArgKind::Message,
             Self::Constructor => AttributeArgKind::Constructor,
+            Self::Receive => AttributeArgKind::Receive,
             Self::Payable => AttributeArgKind::Payable,
             Self::Selector(_) => AttributeArgKind::Selector,
             Self::Namespace(_) => AttributeArgKind::Namespace,
             Self::Implementation => AttributeArgKind::Implementation,
+            Self::Caller(_) => AttributeArgKind::Caller,
+            Self::Only(_) => AttributeArgKind::Only,
+            Self::Roles(_) => AttributeArgKind::Roles,
+            Self::Ownable => AttributeArgKind::Ownable,
+            Self::Pausable => AttributeArgKind::Pausable,
+            Self::WhenNotPaused => AttributeArgKind::WhenNotPaused,
+            Self::Nonreentrant => AttributeArgKind::Nonreentrant,
+            Self::Transient => AttributeArgKind::Transient,
+            Self::Immutable => AttributeArgKind::Immutable,
+            Self::Default(_) => AttributeArgKind::Default,
+            Self::Version(_) => AttributeArgKind::Version,
+            Self::Migrate(_) => AttributeArgKind::Migrate,
+            Self::Encoding(_) => AttributeArgKind::Encoding,
+            Self::NoDerive => AttributeArgKind::NoDerive,
             _ => AttributeArgKind::Actor,
         }
     }
@@ -313,6 +490,28 @@ pub fn contains_fvm_attributes<'a, I>(attrs: I) -> bool
 }
 
 
+/// Returns `true` if one of the `#[derive(...)]` attributes derives a trait named `trait_name`,
+/// comparing the last segment of the derived paths.
+pub fn derives<'a, I>(attrs: I, trait_name: &str) -> bool
+    where
+        I: IntoIterator<Item=&'a syn::Attribute>,
+{
+    attrs
+        .into_iter()
+        .filter(|attr| attr.path.is_ident("derive"))
+        .filter_map(|attr| attr.parse_meta().ok())
+        .any(|meta| match meta {
+            syn::Meta::List(list) => list.nested.iter().any(|nested| {
+                matches!(
+                    nested,
+                    syn::NestedMeta::Meta(syn::Meta::Path(path))
+                        if path.segments.last().is_some_and(|segment| segment.ident == trait_name)
+                )
+            }),
+            _ => false,
+        })
+}
+
 pub fn first_fvm_attribute<'a, I>(
     attrs: I,
 ) -> Result<Option<attrs::FvmAttribute>, syn::Error>
@@ -327,17 +526,26 @@ pub fn first_fvm_attribute<'a, I>(
 }
 
 pub fn sanitize_attributes<I, C>(
-    _: Span,
+    parent_span: Span,
     attrs: I,
-    _: &AttributeArgKind,
-    _: C,
+    is_valid_first: &AttributeArgKind,
+    is_conflicting_attr: C,
 ) -> Result<(FvmAttribute, Vec<syn::Attribute>), syn::Error>
     where
         I: IntoIterator<Item=syn::Attribute>,
         C: FnMut(&AttributeFrag) -> Result<(), Option<syn::Error>>,
 {
     let (fvm_attrs, other_attrs) = partition_attributes(attrs)?;
-    let normalized = FvmAttribute::from_expanded(fvm_attrs).unwrap();
+    let normalized = FvmAttribute::from_expanded(fvm_attrs)?;
+    // Attributes made of the arguments known before they were validated are accepted
+    // unchecked as before, so that existing contracts keep compiling.
+    if normalized.args().all(|arg| arg.kind().kind().is_unchecked()) {
+        return Ok((normalized, other_attrs));
+    }
+    normalized.ensure_first(is_valid_first).map_err(|err| {
+        err.into_combine(format_err!(parent_span, "at this invocation",))
+    })?;
+    normalized.ensure_no_conflicts(is_conflicting_attr)?;
     Ok((normalized, other_attrs))
 }
 
@@ -374,6 +582,7 @@ pub enum AttributeArgKind {
     Topic,
     Message,
     Constructor,
+    Receive,
     Payable,
     Selector,
     Extension,
@@ -381,6 +590,39 @@ pub enum AttributeArgKind {
     Implementation,
     HandleStatus,
     ReturnsResult,
+    Caller,
+    Only,
+    Roles,
+    Ownable,
+    Pausable,
+    WhenNotPaused,
+    Nonreentrant,
+    Transient,
+    Immutable,
+    Default,
+    Version,
+    Migrate,
+    Encoding,
+    NoDerive,
+}
+
+impl AttributeArgKind {
+    /// Returns `true` for the arguments that were accepted in any position and combination
+    /// before the attribute arguments got validated.
+    pub fn is_unchecked(&self) -> bool {
+        matches!(
+            self,
+            Self::Actor
+                | Self::State
+                | Self::Event
+                | Self::Anonymous
+                | Self::Topic
+                | Self::Message
+                | Self::Constructor
+                | Self::Payable
+                | Self::Implementation
+        )
+    }
 }
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
@@ -389,6 +631,23 @@ pub enum SelectorOrWildcard {
     UserProvided(selector::Selector),
 }
 
+impl TryFrom<&syn::Lit> for SelectorOrWildcard {
+    type Error = syn::Error;
+
+    fn try_from(lit: &syn::Lit) -> Result<Self, Self::Error> {
+        match lit {
+            syn::Lit::Int(lit_int) => lit_int
+                .base10_parse::<u32>()
+                .map(|selector| Self::UserProvided(selector::Selector::from(selector.to_be_bytes()))),
+            syn::Lit::Str(lit_str) if lit_str.value() == "_" => Ok(Self::Wildcard),
+            _ => Err(format_err_spanned!(
+                lit,
+                "expected `_` or an integer literal for the selector"
+            )),
+        }
+    }
+}
+
 impl core::fmt::Display for SelectorOrWildcard {
     fn fmt(&self, f: &mut core::fmt::Formatter) -> Result<(), core::fmt::Error> {
         match self {
@@ -399,6 +658,78 @@ impl core::fmt::Display for SelectorOrWildcard {
 }
 
 
+/// How the state struct is serialized, set through `#[fvm_macro(state, encoding = "...")]`.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Encoding {
+    /// A CBOR array of the field values, which only allows appending fields.
+    #[default]
+    Tuple,
+    /// A CBOR map keyed by the field names, tolerating unknown and missing fields on load.
+    Map,
+}
+
+impl Encoding {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Tuple => "tuple",
+            Self::Map => "map",
+        }
+    }
+}
+
+impl TryFrom<&syn::Lit> for Encoding {
+    type Error = syn::Error;
+
+    fn try_from(lit: &syn::Lit) -> Result<Self, Self::Error> {
+        match lit {
+            syn::Lit::Str(lit_str) => match lit_str.value().as_str() {
+                "tuple" => Ok(Self::Tuple),
+                "map" => Ok(Self::Map),
+                _ => Err(format_err_spanned!(
+                    lit_str,
+                    "expected `\"tuple\"` or `\"map\"` for the state encoding"
+                )),
+            },
+            _ => Err(format_err_spanned!(lit, "expected a string literal for the state encoding")),
+        }
+    }
+}
+
+/// The initial value of a state field, set through `#[fvm_macro(default = expr)]`.
+///
+/// The expression is kept as source text so that the attribute argument stays comparable.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct DefaultValue {
+    expr: String,
+}
+
+impl DefaultValue {
+    /// Parses the expression, spanning all of its tokens to `span`.
+    pub fn to_expr(&self, span: Span) -> syn::Expr {
+        let tokens = syn::parse_str::<TokenStream2>(&self.expr)
+            .expect("checked to be a valid expression when parsing the attribute");
+        syn::parse2(respan(tokens, span))
+            .expect("checked to be a valid expression when parsing the attribute")
+    }
+}
+
+impl TryFrom<&syn::Lit> for DefaultValue {
+    type Error = syn::Error;
+
+    fn try_from(lit: &syn::Lit) -> Result<Self, Self::Error> {
+        match lit {
+            syn::Lit::Str(lit_str) => {
+                let expr = lit_str.value();
+                syn::parse_str::<syn::Expr>(&expr).map_err(|_| {
+                    format_err_spanned!(lit_str, "expected an expression for the default value")
+                })?;
+                Ok(Self { expr })
+            }
+            _ => Err(format_err_spanned!(lit, "expected an expression for the default value")),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct Namespace {
     bytes: Vec<u8>,
@@ -414,4 +745,397 @@ impl Namespace {
     pub fn as_bytes(&self) -> &[u8] {
         &self.bytes
     }
-}
\ No newline at end of file
+
+    /// Returns the namespace as a string, checked to be an identifier when parsing it.
+    pub fn as_str(&self) -> &str {
+        core::str::from_utf8(&self.bytes).expect("encountered non-UTF-8 namespace")
+    }
+}
+
+impl TryFrom<&syn::Lit> for Namespace {
+    type Error = syn::Error;
+
+    fn try_from(lit: &syn::Lit) -> Result<Self, Self::Error> {
+        match lit {
+            syn::Lit::Str(lit_str) => {
+                let namespace = lit_str.value();
+                syn::parse_str::<Ident>(&namespace).map_err(|_| {
+                    format_err_spanned!(lit_str, "`{}` is not a valid identifier for the namespace", namespace)
+                })?;
+                Ok(Self::from(namespace.into_bytes()))
+            }
+            _ => Err(format_err_spanned!(lit, "expected a string literal for the namespace")),
+        }
+    }
+}
+
+/// Who is allowed to invoke the constructor of a contract.
+///
+/// Set through `#[fvm_macro(constructor, caller = ...)]`, where the value is one of
+/// `"init"`, `"any"`, an actor ID integer or an address string such as `"f01234"`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum CallerPolicy {
+    /// Only the init actor (`f01`) may construct the contract.
+    #[default]
+    Init,
+    /// The constructor may be invoked by any actor.
+    Any,
+    /// Only the actor with the given ID may construct the contract.
+    ActorId(u64),
+    /// Only the actor the given address resolves to may construct the contract.
+    Address(String),
+}
+
+impl TryFrom<&syn::Lit> for CallerPolicy {
+    type Error = syn::Error;
+
+    fn try_from(lit: &syn::Lit) -> Result<Self, Self::Error> {
+        match lit {
+            syn::Lit::Int(lit_int) => lit_int.base10_parse::<u64>().map(Self::ActorId),
+            syn::Lit::Str(lit_str) => {
+                let value = lit_str.value();
+                match value.as_str() {
+                    "init" => Ok(Self::Init),
+                    "any" => Ok(Self::Any),
+                    address if address.starts_with(['f', 't']) => validate_address(address)
+                        .map(|()| Self::Address(value.clone()))
+                        .map_err(|reason| {
+                            format_err_spanned!(
+                                lit_str,
+                                "invalid address `{}` for the caller policy: {}",
+                                value,
+                                reason
+                            )
+                        }),
+                    _ => Err(format_err_spanned!(
+                        lit_str,
+                        "expected `\"init\"`, `\"any\"`, an actor ID or an address for the caller policy"
+                    )),
+                }
+            }
+            _ => Err(format_err_spanned!(
+                lit,
+                "expected a string or integer literal for the caller policy"
+            )),
+        }
+    }
+}
+
+/// Parses a string literal holding one or more comma separated identifiers.
+///
+/// The identifiers are spanned to the literal so that errors about them point at the attribute.
+fn parse_idents(lit: &syn::Lit) -> Result<Vec<Ident>, syn::Error> {
+    let lit_str = match lit {
+        syn::Lit::Str(lit_str) => lit_str,
+        _ => return Err(format_err_spanned!(lit, "expected a string literal")),
+    };
+    let idents = lit_str
+        .value()
+        .split(',')
+        .map(str::trim)
+        .map(|name| {
+            syn::parse_str::<Ident>(name)
+                .map(|ident| Ident::new(&ident.to_string(), lit_str.span()))
+                .map_err(|_| format_err_spanned!(lit_str, "`{}` is not a valid identifier", name))
+        })
+        .collect::<Result<Vec<_>, syn::Error>>()?;
+    Ok(idents)
+}
+
+fn parse_version(lit: &syn::Lit) -> Result<u64, syn::Error> {
+    match lit {
+        syn::Lit::Int(lit_int) => lit_int.base10_parse::<u64>(),
+        _ => Err(format_err_spanned!(lit, "expected an integer literal for the version")),
+    }
+}
+
+/// Parses the version of `#[fvm_macro(state, version = N)]`, which starts at 1.
+///
+/// Version 0 stands for the roots written before the state was versioned, which have no
+/// envelope, so a state can not be stored at it.
+fn parse_state_version(lit: &syn::Lit) -> Result<u64, syn::Error> {
+    match parse_version(lit)? {
+        0 => Err(format_err_spanned!(
+            lit,
+            "the state version starts at 1, version 0 stands for the state before it was versioned"
+        )),
+        version => Ok(version),
+    }
+}
+
+/// Parses the version of `migrate(from = N)`.
+fn parse_migrate(meta_list: &syn::MetaList) -> Result<u64, syn::Error> {
+    let mut nested = meta_list.nested.iter();
+    match (nested.next(), nested.next()) {
+        (Some(syn::NestedMeta::Meta(syn::Meta::NameValue(name_value))), None)
+            if name_value.path.is_ident("from") =>
+        {
+            parse_version(&name_value.lit)
+        }
+        _ => Err(format_err_spanned!(
+            meta_list,
+            "expected `migrate(from = N)` with the state version to migrate from"
+        )),
+    }
+}
+
+/// Replaces the `_` of `selector = _` with a string literal, since `syn::Meta` only accepts
+/// literals as values.
+fn quote_wildcard_selector(tokens: TokenStream2) -> TokenStream2 {
+    let mut trees = tokens.into_iter().collect::<Vec<_>>();
+    for index in 0..trees.len() {
+        match &trees[index] {
+            TokenTree::Group(group) => {
+                let mut quoted = Group::new(group.delimiter(), quote_wildcard_selector(group.stream()));
+                quoted.set_span(group.span());
+                trees[index] = TokenTree::Group(quoted);
+            }
+            TokenTree::Ident(wildcard) if wildcard == "_" && index >= 2 => {
+                let is_selector = matches!(
+                    (&trees[index - 2], &trees[index - 1]),
+                    (TokenTree::Ident(name), TokenTree::Punct(eq)) if name == "selector" && eq.as_char() == '='
+                );
+                if is_selector {
+                    let mut literal = Literal::string("_");
+                    literal.set_span(wildcard.span());
+                    trees[index] = TokenTree::Literal(literal);
+                }
+            }
+            _ => {}
+        }
+    }
+    trees.into_iter().collect()
+}
+
+/// Replaces the expression of `default = expr` with a string literal holding its source, since
+/// `syn::Meta` only accepts literals as values.
+///
+/// The expression ends at the next comma outside of a delimited group, so an expression with
+/// top level commas such as `HashMap::<K, V>::new()` must be wrapped in braces.
+fn quote_default_value(tokens: TokenStream2) -> TokenStream2 {
+    let mut trees = tokens.into_iter().peekable();
+    let mut quoted = Vec::new();
+    while let Some(tree) = trees.next() {
+        match tree {
+            TokenTree::Group(group) => {
+                let mut inner = Group::new(group.delimiter(), quote_default_value(group.stream()));
+                inner.set_span(group.span());
+                quoted.push(TokenTree::Group(inner));
+            }
+            TokenTree::Punct(eq) if eq.as_char() == '=' && matches!(
+                quoted.last(),
+                Some(TokenTree::Ident(name)) if name == "default"
+            ) => {
+                quoted.push(TokenTree::Punct(eq));
+                let mut expr = Vec::new();
+                while let Some(tree) = trees.next_if(
+                    |tree| !matches!(tree, TokenTree::Punct(comma) if comma.as_char() == ',')
+                ) {
+                    expr.push(tree);
+                }
+                let span = expr.first().map_or_else(Span::call_site, TokenTree::span);
+                let mut literal = Literal::string(&expr.into_iter().collect::<TokenStream2>().to_string());
+                literal.set_span(span);
+                quoted.push(TokenTree::Literal(literal));
+            }
+            tree => quoted.push(tree),
+        }
+    }
+    quoted.into_iter().collect()
+}
+
+/// Sets the span of every token, including the ones nested in groups.
+fn respan(tokens: TokenStream2, span: Span) -> TokenStream2 {
+    tokens
+        .into_iter()
+        .map(|mut tree| {
+            if let TokenTree::Group(group) = &tree {
+                tree = TokenTree::Group(Group::new(group.delimiter(), respan(group.stream(), span)));
+            }
+            tree.set_span(span);
+            tree
+        })
+        .collect()
+}
+
+/// Checks that `value` is a Filecoin address in its string form, including the checksum.
+///
+/// This mirrors `fvm_shared::address::Address::from_str`, so that the generated code can
+/// rely on the address to parse.
+fn validate_address(value: &str) -> Result<(), &'static str> {
+    const PAYLOAD_HASH_LEN: usize = 20;
+    const BLS_PUB_LEN: usize = 48;
+    const CHECKSUM_HASH_LEN: usize = 4;
+
+    let mut chars = value.chars();
+    if !matches!(chars.next(), Some('f' | 't')) {
+        return Err("unknown network");
+    }
+    let protocol = match chars.next() {
+        Some(protocol @ '0'..='3') => protocol as u8 - b'0',
+        _ => return Err("unknown protocol"),
+    };
+    let raw = chars.as_str();
+    if protocol == 0 {
+        if raw.len() > 20 || raw.parse::<u64>().is_err() {
+            return Err("invalid actor ID");
+        }
+        return Ok(());
+    }
+    let mut payload = decode_base32(raw).ok_or("invalid base32 payload")?;
+    if payload.len() < CHECKSUM_HASH_LEN {
+        return Err("invalid payload length");
+    }
+    let checksum = payload.split_off(payload.len() - CHECKSUM_HASH_LEN);
+    let payload_len = if protocol == 3 { BLS_PUB_LEN } else { PAYLOAD_HASH_LEN };
+    if payload.len() != payload_len {
+        return Err("invalid payload length");
+    }
+    let mut expected = [0u8; CHECKSUM_HASH_LEN];
+    blake2b_32(&[&[protocol][..], &payload].concat(), &mut expected);
+    if checksum != expected {
+        return Err("invalid checksum");
+    }
+    Ok(())
+}
+
+/// Decodes unpadded lower case base32 as used by Filecoin addresses.
+fn decode_base32(raw: &str) -> Option<Vec<u8>> {
+    let mut bytes = Vec::with_capacity(raw.len() * 5 / 8);
+    let (mut buffer, mut bits) = (0u16, 0u32);
+    for c in raw.bytes() {
+        let value = match c {
+            b'a'..=b'z' => c - b'a',
+            b'2'..=b'7' => c - b'2' + 26,
+            _ => return None,
+        };
+        buffer = (buffer << 5) | u16::from(value);
+        bits += 5;
+        if bits >= 8 {
+            bits -= 8;
+            bytes.push((buffer >> bits) as u8);
+            buffer &= (1 << bits) - 1;
+        }
+    }
+    // A complete encoding leaves fewer than 5 bits over, all of them zero.
+    (bits < 5 && buffer == 0).then_some(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse_first(input: syn::Attribute) -> Result<AttributeArg, syn::Error> {
+        let attr = FvmAttribute::try_from(input)?;
+        Ok(attr.args().nth(1).expect("missing second argument").kind().clone())
+    }
+
+    #[test]
+    fn caller_policy_works() {
+        let cases: Vec<(syn::Attribute, CallerPolicy)> = vec![
+            (syn::parse_quote! { #[fvm_macro(constructor, caller = "init")] }, CallerPolicy::Init),
+            (syn::parse_quote! { #[fvm_macro(constructor, caller = "any")] }, CallerPolicy::Any),
+            (syn::parse_quote! { #[fvm_macro(constructor, caller = 1042)] }, CallerPolicy::ActorId(1042)),
+            (
+                syn::parse_quote! { #[fvm_macro(constructor, caller = "t01042")] },
+                CallerPolicy::Address("t01042".to_string()),
+            ),
+            (
+                syn::parse_quote! { #[fvm_macro(constructor, caller = "f1aebagbafaydqqcikbmga2dqpcaireeyuokqsccy")] },
+                CallerPolicy::Address("f1aebagbafaydqqcikbmga2dqpcaireeyuokqsccy".to_string()),
+            ),
+        ];
+        for (attr, expected) in cases {
+            assert_eq!(parse_first(attr).unwrap(), AttributeArg::Caller(expected));
+        }
+    }
+
+    #[test]
+    fn invalid_caller_address_fails() {
+        let cases: Vec<syn::Attribute> = vec![
+            syn::parse_quote! { #[fvm_macro(constructor, caller = "f0")] },
+            syn::parse_quote! { #[fvm_macro(constructor, caller = "t0abc")] },
+            syn::parse_quote! { #[fvm_macro(constructor, caller = "f5aebagbafaydqqcikbmga2dqpcaireeyuokqsccy")] },
+            // The checksum of the address for protocol 1 does not match protocol 2.
+            syn::parse_quote! { #[fvm_macro(constructor, caller = "f2aebagbafaydqqcikbmga2dqpcaireeyuokqsccy")] },
+            syn::parse_quote! { #[fvm_macro(constructor, caller = "f1aebagbafaydqqcikbmga2dqpcaireeyuokqscc")] },
+        ];
+        for attr in cases {
+            assert!(parse_first(attr).is_err());
+        }
+    }
+
+    #[test]
+    fn access_rules_work() {
+        let attr: syn::Attribute = syn::parse_quote! {
+            #[fvm_macro(message, only = "owner", roles = "minter, burner")]
+        };
+        let attr = FvmAttribute::try_from(attr).unwrap();
+        assert_eq!(attr.only().unwrap().to_string(), "owner");
+        assert_eq!(
+            attr.roles().iter().map(ToString::to_string).collect::<Vec<_>>(),
+            vec!["minter", "burner"],
+        );
+    }
+
+    #[test]
+    fn wildcard_selector_works() {
+        let attr: syn::Attribute = syn::parse_quote! { #[fvm_macro(message, selector = _)] };
+        assert_eq!(parse_first(attr).unwrap(), AttributeArg::Selector(SelectorOrWildcard::Wildcard));
+        let attr: syn::Attribute = syn::parse_quote! { #[fvm_macro(message, selector = 42)] };
+        assert_eq!(
+            parse_first(attr).unwrap(),
+            AttributeArg::Selector(SelectorOrWildcard::UserProvided(selector::Selector::from([0, 0, 0, 42]))),
+        );
+    }
+
+    #[test]
+    fn default_value_works() {
+        let attr: syn::Attribute = syn::parse_quote! {
+            #[fvm_macro(default = Vec::from([1, 2]), immutable)]
+        };
+        let attr = FvmAttribute::try_from(attr).unwrap();
+        let expected: syn::Expr = syn::parse_quote! { Vec::from([1, 2]) };
+        assert_eq!(attr.default_value().unwrap(), expected);
+        assert!(attr.is_immutable());
+    }
+
+    #[test]
+    fn versioning_works() {
+        let attr: syn::Attribute = syn::parse_quote! { #[fvm_macro(state, version = 3)] };
+        assert_eq!(FvmAttribute::try_from(attr).unwrap().version(), Some(3));
+        let attr: syn::Attribute = syn::parse_quote! { #[fvm_macro(migrate(from = 2))] };
+        assert_eq!(FvmAttribute::try_from(attr).unwrap().migrate_from(), Some(2));
+        let attr: syn::Attribute = syn::parse_quote! { #[fvm_macro(migrate(to = 2))] };
+        assert!(FvmAttribute::try_from(attr).is_err());
+        let attr: syn::Attribute = syn::parse_quote! { #[fvm_macro(state, encoding = "map")] };
+        assert_eq!(FvmAttribute::try_from(attr).unwrap().encoding(), Some(Encoding::Map));
+    }
+
+    #[test]
+    fn derives_works() {
+        let attrs: Vec<syn::Attribute> = vec![
+            syn::parse_quote! { #[derive(Clone, core::fmt::Debug)] },
+            syn::parse_quote! { #[fvm_macro(state, no_derive)] },
+        ];
+        assert!(derives(&attrs, "Clone"));
+        assert!(derives(&attrs, "Debug"));
+        assert!(!derives(&attrs, "Default"));
+        assert!(FvmAttribute::try_from(attrs[1].clone()).unwrap().is_no_derive());
+    }
+
+    #[test]
+    fn namespace_works() {
+        let attr: syn::Attribute = syn::parse_quote! { #[fvm_macro(impl, namespace = "FRC42")] };
+        let attr = FvmAttribute::try_from(attr).unwrap();
+        assert_eq!(attr.namespace().unwrap().as_str(), "FRC42");
+        let attr: syn::Attribute = syn::parse_quote! { #[fvm_macro(impl, namespace = "FRC 42")] };
+        assert!(FvmAttribute::try_from(attr).is_err());
+    }
+
+    #[test]
+    fn caller_policy_rejects_unknown_names() {
+        let attr: syn::Attribute = syn::parse_quote! { #[fvm_macro(constructor, caller = "anyone")] };
+        assert!(parse_first(attr).is_err());
+    }
+}
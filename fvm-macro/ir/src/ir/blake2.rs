@@ -31,6 +31,23 @@ pub fn blake2b_256(input: &[u8], output: &mut [u8; 32]) {
     output.copy_from_slice(&result);
 }
 
+/// Computes the BLAKE-2b 32-bit hash for the given input and stores it in output.
+///
+/// This is the checksum of Filecoin addresses.
+pub fn blake2b_32(input: &[u8], output: &mut [u8; 4]) {
+    use ::blake2::digest::{
+        consts::U4,
+        Digest as _,
+    };
+
+    type Blake2b32 = ::blake2::Blake2b<U4>;
+
+    let mut blake2 = Blake2b32::new();
+    blake2.update(input);
+    let result = blake2.finalize();
+    output.copy_from_slice(&result);
+}
+
 /// Computes the BLAKE2b-256 bit hash of a string or byte string literal.
 ///
 /// # Note
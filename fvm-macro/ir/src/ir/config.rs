@@ -0,0 +1,125 @@
+use crate::format_err_spanned;
+use proc_macro2::{
+    Ident,
+    TokenStream as TokenStream2,
+};
+use syn::parse::Parser as _;
+
+/// The configuration given through the arguments of `#[fvm_macro::contract(...)]`.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct Config {
+    rbac: bool,
+    batch: bool,
+    instantiate: Option<syn::Type>,
+    entry: Option<Entry>,
+}
+
+/// Whether a contract exports the `invoke` entry point of the wasm module, set through
+/// `#[fvm_macro::contract(entry = ...)]`.
+///
+/// Only one contract of a crate may export it, so the export is opt-in: the contract meant as the
+/// entry point is declared with `entry`, or several are gated behind distinct features.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub enum Entry {
+    /// `entry` or `entry = true`.
+    Always,
+    /// `entry = false` or no `entry` argument, the default.
+    #[default]
+    Never,
+    /// `entry = "feature"`, exported if the crate is built with the feature.
+    Feature(String),
+}
+
+impl TryFrom<TokenStream2> for Config {
+    type Error = syn::Error;
+
+    fn try_from(args: TokenStream2) -> Result<Self, Self::Error> {
+        let args = syn::punctuated::Punctuated::<syn::NestedMeta, syn::Token![,]>::parse_terminated
+            .parse2(args)?;
+        let mut config = Config::default();
+        for arg in args {
+            if let syn::NestedMeta::Meta(syn::Meta::NameValue(name_value)) = &arg {
+                if name_value.path.is_ident("instantiate") {
+                    if config.instantiate.is_some() {
+                        return Err(format_err_spanned!(arg, "encountered duplicate fvm contract argument"));
+                    }
+                    config.instantiate = Some(parse_type(&name_value.lit)?);
+                    continue;
+                }
+                if name_value.path.is_ident("entry") {
+                    if config.entry.is_some() {
+                        return Err(format_err_spanned!(arg, "encountered duplicate fvm contract argument"));
+                    }
+                    config.entry = Some(parse_entry(&name_value.lit)?);
+                    continue;
+                }
+            }
+            if matches!(&arg, syn::NestedMeta::Meta(syn::Meta::Path(path)) if path.is_ident("entry")) {
+                if config.entry.is_some() {
+                    return Err(format_err_spanned!(arg, "encountered duplicate fvm contract argument"));
+                }
+                config.entry = Some(Entry::Always);
+                continue;
+            }
+            let ident = match &arg {
+                syn::NestedMeta::Meta(syn::Meta::Path(path)) => path.get_ident().map(Ident::to_string),
+                _ => None,
+            };
+            let flag = match ident.as_deref() {
+                Some("rbac") => &mut config.rbac,
+                Some("batch") => &mut config.batch,
+                _ => return Err(format_err_spanned!(arg, "unknown fvm contract argument")),
+            };
+            if *flag {
+                return Err(format_err_spanned!(arg, "encountered duplicate fvm contract argument"));
+            }
+            *flag = true;
+        }
+        Ok(config)
+    }
+}
+
+impl Config {
+    /// Returns `true` if the contract opted in to the built-in role-based access control.
+    pub fn rbac(&self) -> bool {
+        self.rbac
+    }
+
+    /// Returns `true` if the contract opted in to the generated `batch` message.
+    pub fn batch(&self) -> bool {
+        self.batch
+    }
+
+    /// Returns the concrete type a generic state is instantiated with for the `invoke` export.
+    pub fn instantiate(&self) -> Option<&syn::Type> {
+        self.instantiate.as_ref()
+    }
+
+    /// Returns whether the contract exports the `invoke` entry point.
+    pub fn entry(&self) -> Entry {
+        self.entry.clone().unwrap_or_default()
+    }
+}
+
+/// Parses the type of `instantiate = "MyState<Foo>"`, spanned to the literal.
+fn parse_type(lit: &syn::Lit) -> Result<syn::Type, syn::Error> {
+    match lit {
+        syn::Lit::Str(lit_str) => lit_str.parse::<syn::Type>().map_err(|_| {
+            format_err_spanned!(lit_str, "expected a type such as `\"MyState<Foo>\"` to instantiate")
+        }),
+        _ => Err(format_err_spanned!(lit, "expected a string literal holding the type to instantiate")),
+    }
+}
+
+/// Parses the value of `entry = true`, `entry = false` or `entry = "feature"`.
+fn parse_entry(lit: &syn::Lit) -> Result<Entry, syn::Error> {
+    match lit {
+        syn::Lit::Bool(lit_bool) if lit_bool.value => Ok(Entry::Always),
+        syn::Lit::Bool(_) => Ok(Entry::Never),
+        syn::Lit::Str(lit_str) if !lit_str.value().is_empty() => Ok(Entry::Feature(lit_str.value())),
+        _ => Err(format_err_spanned!(
+            lit,
+            "expected `true`, `false` or the name of a feature for the contract entry point"
+        )),
+    }
+}
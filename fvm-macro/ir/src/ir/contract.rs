@@ -1,21 +1,33 @@
 use crate::ir;
-use ir::item_mod;
+use ir::{config, item_mod};
 use proc_macro2::TokenStream;
 
 #[derive(Debug)]
 pub struct Contract {
     item: item_mod::ItemMod,
+    config: config::Config,
 }
 
 impl Contract {
-    pub fn new(fvm_item: TokenStream) -> Result<Self, syn::Error> {
-        let module = syn::parse2::<syn::ItemMod>(fvm_item).unwrap();
-        let fvm_module = item_mod::ItemMod::try_from(module).unwrap();
-        Ok(Self { item: fvm_module })
+    pub fn new(fvm_config: TokenStream, fvm_item: TokenStream) -> Result<Self, syn::Error> {
+        let config = config::Config::try_from(fvm_config)?;
+        let module = syn::parse2::<syn::ItemMod>(fvm_item)?;
+        let fvm_module = item_mod::ItemMod::try_from(module)?;
+        fvm_module.ensure_component_fields(&config)?;
+        fvm_module.ensure_access_fields(&config)?;
+        fvm_module.ensure_pausable(&config)?;
+        fvm_module.ensure_state_shape(&config)?;
+        fvm_module.ensure_instantiate(&config)?;
+        fvm_module.ensure_migrations()?;
+        fvm_module.ensure_no_overlapping_selectors(&config)?;
+        Ok(Self { item: fvm_module, config })
     }
 
     pub fn module(&self) -> &item_mod::ItemMod {
         &self.item
     }
-}
 
+    pub fn config(&self) -> &config::Config {
+        &self.config
+    }
+}
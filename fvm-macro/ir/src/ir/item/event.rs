@@ -6,9 +6,6 @@ use proc_macro2::{
     Span,
 };
 use syn::spanned::Spanned as _;
-use crate::{
-    format_err_spanned,
-};
 
 #[derive(Debug, PartialEq, Eq)]
 pub struct Event {
@@ -40,12 +37,6 @@ impl TryFrom<syn::ItemStruct> for Event {
                 }
             },
         )?;
-        if !item_struct.generics.params.is_empty() {
-            return Err(format_err_spanned!(
-                item_struct.generics.params,
-                "generic fvm! event structs are not supported",
-            ));
-        }
         Ok(Self {
             item: syn::ItemStruct {
                 attrs: other_attrs,
@@ -61,13 +52,21 @@ impl Event {
         &self.item.ident
     }
 
-    pub fn fields(&self) -> EventFieldsIter {
+    pub fn generics(&self) -> &syn::Generics {
+        &self.item.generics
+    }
+
+    pub fn fields(&self) -> EventFieldsIter<'_> {
         EventFieldsIter::new(self)
     }
 
     pub fn attrs(&self) -> &[syn::Attribute] {
         &self.item.attrs
     }
+
+    pub fn vis(&self) -> &syn::Visibility {
+        &self.item.vis
+    }
 }
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
@@ -98,6 +97,10 @@ impl<'a> EventField<'a> {
     pub fn ty(self) -> &'a syn::Type {
         &self.field.ty
     }
+
+    pub fn field(self) -> &'a syn::Field {
+        self.field
+    }
 }
 
 pub struct EventFieldsIter<'a> {
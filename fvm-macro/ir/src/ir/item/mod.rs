@@ -5,6 +5,8 @@ pub mod event;
 use core::result::Result;
 use either::Either;
 use itertools::Itertools;
+use crate::format_err;
+use syn::spanned::Spanned as _;
 use crate::ir::{
     attrs,
     attrs::{
@@ -54,7 +56,10 @@ impl TryFrom<syn::Item> for Item {
 
         match item {
             syn::Item::Struct(item_struct) => {
-                let attr = attrs::first_fvm_attribute(&item_struct.attrs)?.expect("missing expected fvm_macro attribute for struct");
+                let attr = match attrs::first_fvm_attribute(&item_struct.attrs)? {
+                    Some(attr) => attr,
+                    None => return Ok(Self::Rust(item_struct.into())),
+                };
 
                 match attr.first().kind() {
                     attrs::AttributeArg::State => {
@@ -64,6 +69,11 @@ impl TryFrom<syn::Item> for Item {
                             .map(Self::Fvm);
                         return value;
                     }
+                    attrs::AttributeArg::Event => {
+                        return <event::Event as TryFrom<_>>::try_from(item_struct)
+                            .map(Into::into)
+                            .map(Self::Fvm);
+                    }
 
                     _ => {}
                 }
@@ -92,6 +102,20 @@ impl TryFrom<syn::Item> for Item {
 
                 Ok(Self::Rust(syn::Item::Struct(ok)))
             }
+            syn::Item::Enum(item_enum) => {
+                match attrs::first_fvm_attribute(&item_enum.attrs)? {
+                    Some(attr) if matches!(attr.first().kind(), attrs::AttributeArg::State) => {
+                        <state::State as TryFrom<_>>::try_from(item_enum)
+                            .map(Into::into)
+                            .map(Self::Fvm)
+                    }
+                    Some(attr) => Err(format_err!(
+                        attr.span(),
+                        "encountered unexpected fvm attribute, only `#[fvm_macro(state)]` is supported on enums",
+                    )),
+                    None => Ok(Self::Rust(item_enum.into())),
+                }
+            }
             syn::Item::Impl(item_impl) => {
                 if !item_impl::ItemImpl::is_fvm_impl_block(&item_impl)? {
                     return Ok(Self::Rust(item_impl.into()));
@@ -1,13 +1,38 @@
-use crate::format_err_spanned;
+use crate::{format_err, format_err_spanned};
 use crate::ir::{
     attrs,
 };
+use either::Either;
 use proc_macro2::Ident;
 use syn::spanned::Spanned as _;
 
 #[derive(Debug, PartialEq, Eq, Clone)]
 pub struct State {
-    ast: syn::ItemStruct,
+    ast: StateItem,
+    ownable: bool,
+    pausable: bool,
+    version: Option<u64>,
+    encoding: attrs::Encoding,
+    no_derive: bool,
+    transient: Vec<Ident>,
+    immutable: Vec<Ident>,
+    defaults: Vec<(Ident, syn::Expr)>,
+}
+
+/// The item declaring the state: a struct with named or unnamed fields, or an enum.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum StateItem {
+    Struct(syn::ItemStruct),
+    Enum(syn::ItemEnum),
+}
+
+impl quote::ToTokens for StateItem {
+    fn to_tokens(&self, tokens: &mut proc_macro2::TokenStream) {
+        match self {
+            Self::Struct(item_struct) => item_struct.to_tokens(tokens),
+            Self::Enum(item_enum) => item_enum.to_tokens(tokens),
+        }
+    }
 }
 
 impl quote::ToTokens for State {
@@ -19,46 +44,311 @@ impl quote::ToTokens for State {
 impl TryFrom<syn::ItemStruct> for State {
     type Error = syn::Error;
 
-    fn try_from(item_struct: syn::ItemStruct) -> Result<Self, Self::Error> {
-        let struct_span = item_struct.span();
+    fn try_from(mut item_struct: syn::ItemStruct) -> Result<Self, Self::Error> {
+        let fields = Self::sanitize_field_attributes(&mut item_struct.fields)?;
+        let fvm_attrs = Self::sanitize_attributes(item_struct.span(), &mut item_struct.attrs)?;
+        Self::ensure_no_serde_derives(&item_struct.attrs)?;
+        if !fields.defaults.is_empty() && attrs::derives(&item_struct.attrs, "Default") {
+            return Err(format_err_spanned!(
+                item_struct.ident,
+                "fvm states with `default` field values implement `Default` themselves, remove the derive",
+            ));
+        }
+        if !matches!(item_struct.fields, syn::Fields::Named(_)) {
+            Self::ensure_named_fields_args(&fvm_attrs, "tuple and unit")?;
+        }
+        Ok(Self::new(StateItem::Struct(item_struct), &fvm_attrs, fields))
+    }
+}
+
+impl TryFrom<syn::ItemEnum> for State {
+    type Error = syn::Error;
+
+    fn try_from(mut item_enum: syn::ItemEnum) -> Result<Self, Self::Error> {
+        let fvm_field = item_enum
+            .variants
+            .iter()
+            .flat_map(|variant| variant.fields.iter())
+            .find(|field| attrs::contains_fvm_attributes(&field.attrs));
+        if let Some(field) = fvm_field {
+            return Err(format_err_spanned!(
+                field,
+                "fvm field attributes are not supported in enum states",
+            ));
+        }
+        let fvm_attrs = Self::sanitize_attributes(item_enum.span(), &mut item_enum.attrs)?;
+        Self::ensure_no_serde_derives(&item_enum.attrs)?;
+        Self::ensure_named_fields_args(&fvm_attrs, "enum")?;
+        Ok(Self::new(StateItem::Enum(item_enum), &fvm_attrs, FieldAttributes::default()))
+    }
+}
+
+impl State {
+    fn new(ast: StateItem, fvm_attrs: &attrs::FvmAttribute, fields: FieldAttributes) -> Self {
+        Self {
+            ast,
+            ownable: fvm_attrs.is_ownable(),
+            pausable: fvm_attrs.is_pausable(),
+            version: fvm_attrs.version(),
+            encoding: fvm_attrs.encoding().unwrap_or_default(),
+            no_derive: fvm_attrs.is_no_derive(),
+            transient: fields.transient,
+            immutable: fields.immutable,
+            defaults: fields.defaults,
+        }
+    }
 
-        let (_, other_attrs) = attrs::sanitize_attributes(
-            struct_span,
-            item_struct.attrs,
+    /// Strips the fvm attributes of the state item, returning them.
+    fn sanitize_attributes(
+        span: proc_macro2::Span,
+        item_attrs: &mut Vec<syn::Attribute>,
+    ) -> Result<attrs::FvmAttribute, syn::Error> {
+        let (fvm_attrs, other_attrs) = attrs::sanitize_attributes(
+            span,
+            item_attrs.drain(..),
             &attrs::AttributeArgKind::State,
             |arg| {
                 match arg.kind() {
-                    attrs::AttributeArg::State => Ok(()),
+                    attrs::AttributeArg::State
+                    | attrs::AttributeArg::Ownable
+                    | attrs::AttributeArg::Pausable
+                    | attrs::AttributeArg::Version(_)
+                    | attrs::AttributeArg::Encoding(_)
+                    | attrs::AttributeArg::NoDerive => Ok(()),
                     _ => Err(None),
                 }
             },
         )?;
-        if !item_struct.generics.params.is_empty() {
+        *item_attrs = other_attrs;
+        Ok(fvm_attrs)
+    }
+
+    /// Ensures that the state leaves its serde impls to the macro, which encodes it as declared
+    /// in its storage layout.
+    fn ensure_no_serde_derives(item_attrs: &[syn::Attribute]) -> Result<(), syn::Error> {
+        let derive = item_attrs.iter().find(|attr| {
+            attrs::derives(core::iter::once(*attr), "Serialize")
+                || attrs::derives(core::iter::once(*attr), "Deserialize")
+        });
+        if let Some(derive) = derive {
             return Err(format_err_spanned!(
-                item_struct.generics.params,
-                "generic fvm state structs are not supported",
+                derive,
+                "fvm states must not derive `Serialize` or `Deserialize`, their serde impls are generated",
             ));
         }
+        Ok(())
+    }
 
-        Ok(Self {
-            ast: syn::ItemStruct {
-                attrs: other_attrs,
-                ..item_struct
-            },
-        })
+    /// Ensures that a state without named fields uses no argument adding or naming fields.
+    fn ensure_named_fields_args(fvm_attrs: &attrs::FvmAttribute, kind: &str) -> Result<(), syn::Error> {
+        let invalid = fvm_attrs.args().find(|arg| {
+            matches!(
+                arg.kind(),
+                attrs::AttributeArg::Ownable
+                    | attrs::AttributeArg::Pausable
+                    | attrs::AttributeArg::Encoding(attrs::Encoding::Map)
+            )
+        });
+        if let Some(arg) = invalid {
+            return Err(format_err!(
+                arg.span(),
+                "{} fvm states are not supported with this argument, it requires named fields",
+                kind,
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// The field attributes of a state struct.
+#[derive(Default)]
+struct FieldAttributes {
+    transient: Vec<Ident>,
+    immutable: Vec<Ident>,
+    defaults: Vec<(Ident, syn::Expr)>,
+}
+
+impl State {
+    /// Strips the fvm attributes of the fields, returning the fields they apply to.
+    fn sanitize_field_attributes(fields: &mut syn::Fields) -> Result<FieldAttributes, syn::Error> {
+        let mut result = FieldAttributes::default();
+        for field in fields.iter_mut() {
+            if !attrs::contains_fvm_attributes(&field.attrs) {
+                continue;
+            }
+            let (fvm_attrs, other_attrs) = attrs::partition_attributes(field.attrs.clone())?;
+            let fvm_attrs = attrs::FvmAttribute::from_expanded(fvm_attrs)?;
+            fvm_attrs.ensure_no_conflicts(|arg| {
+                match arg.kind() {
+                    attrs::AttributeArg::Transient
+                    | attrs::AttributeArg::Immutable
+                    | attrs::AttributeArg::Default(_) => Ok(()),
+                    _ => Err(None),
+                }
+            })?;
+            if fvm_attrs.is_transient() && (fvm_attrs.is_immutable() || fvm_attrs.default_value().is_some()) {
+                return Err(format_err!(
+                    fvm_attrs.span(),
+                    "transient fields cannot be immutable or have a default value",
+                ));
+            }
+            let ident = field.ident.clone().ok_or_else(|| {
+                format_err_spanned!(field, "fvm field attributes require a named field")
+            })?;
+            if fvm_attrs.is_transient() {
+                result.transient.push(ident.clone());
+            }
+            if fvm_attrs.is_immutable() {
+                result.immutable.push(ident.clone());
+            }
+            if let Some(expr) = fvm_attrs.default_value() {
+                result.defaults.push((ident, expr));
+            }
+            field.attrs = other_attrs;
+        }
+        Ok(result)
     }
 }
 
 impl State {
+    pub fn item(&self) -> &StateItem {
+        &self.ast
+    }
+
     pub fn attrs(&self) -> &[syn::Attribute] {
-        &self.ast.attrs
+        match &self.ast {
+            StateItem::Struct(item_struct) => &item_struct.attrs,
+            StateItem::Enum(item_enum) => &item_enum.attrs,
+        }
+    }
+
+    pub fn vis(&self) -> &syn::Visibility {
+        match &self.ast {
+            StateItem::Struct(item_struct) => &item_struct.vis,
+            StateItem::Enum(item_enum) => &item_enum.vis,
+        }
     }
 
     pub fn ident(&self) -> &Ident {
-        &self.ast.ident
+        match &self.ast {
+            StateItem::Struct(item_struct) => &item_struct.ident,
+            StateItem::Enum(item_enum) => &item_enum.ident,
+        }
+    }
+
+    pub fn generics(&self) -> &syn::Generics {
+        match &self.ast {
+            StateItem::Struct(item_struct) => &item_struct.generics,
+            StateItem::Enum(item_enum) => &item_enum.generics,
+        }
+    }
+
+    /// Returns the fields of a state struct, or nothing for an enum state.
+    pub fn fields(&self) -> impl Iterator<Item = &syn::Field> {
+        match &self.ast {
+            StateItem::Struct(item_struct) => Either::Left(item_struct.fields.iter()),
+            StateItem::Enum(_) => Either::Right(core::iter::empty()),
+        }
+    }
+
+    /// Returns `true` if the state is a struct with named fields, which components can add
+    /// their fields to.
+    pub fn has_named_fields(&self) -> bool {
+        matches!(&self.ast, StateItem::Struct(item_struct) if matches!(item_struct.fields, syn::Fields::Named(_)))
+    }
+
+    /// Returns `true` if the state derives `trait_name` itself.
+    pub fn derives(&self, trait_name: &str) -> bool {
+        attrs::derives(self.attrs(), trait_name)
+    }
+
+    /// Returns `true` if the state derives `Default` itself.
+    pub fn derives_default(&self) -> bool {
+        self.derives("Default")
+    }
+
+    /// Returns `true` if the state opted out of the generated `Clone`, `Debug` and `Default`
+    /// derives with `#[fvm_macro(state, no_derive)]`.
+    pub fn is_no_derive(&self) -> bool {
+        self.no_derive
+    }
+
+    /// Returns `true` if the state opted in to the `owner` and `pending_owner` fields.
+    pub fn is_ownable(&self) -> bool {
+        self.ownable
+    }
+
+    /// Returns `true` if the field is marked `#[fvm_macro(transient)]`, i.e. it is not persisted
+    /// and initialised with `Default` on load.
+    pub fn is_transient(&self, field: &syn::Field) -> bool {
+        field
+            .ident
+            .as_ref()
+            .is_some_and(|ident| self.transient.contains(ident))
+    }
+
+    /// Returns `true` if the field is marked `#[fvm_macro(immutable)]`, i.e. only the constructor
+    /// may change it.
+    pub fn is_immutable(&self, field: &syn::Field) -> bool {
+        field
+            .ident
+            .as_ref()
+            .is_some_and(|ident| self.immutable.contains(ident))
+    }
+
+    /// Returns `true` if the field is persisted as a `Lazy` link, i.e. its type is a path ending
+    /// in `Lazy`, whose value the generated `State::save` flushes.
+    pub fn is_lazy(&self, field: &syn::Field) -> bool {
+        let is_lazy_type = match &field.ty {
+            syn::Type::Path(type_path) => type_path
+                .path
+                .segments
+                .last()
+                .is_some_and(|segment| segment.ident == "Lazy"),
+            _ => false,
+        };
+        is_lazy_type && !self.is_transient(field)
+    }
+
+    /// Returns the `immutable` fields.
+    pub fn immutable_fields(&self) -> impl Iterator<Item = &syn::Field> {
+        self.fields().filter(|field| self.is_immutable(field))
+    }
+
+    /// Returns the initial value given with `#[fvm_macro(default = expr)]` for the field.
+    pub fn default_value(&self, field: &syn::Field) -> Option<&syn::Expr> {
+        let ident = field.ident.as_ref()?;
+        self.defaults
+            .iter()
+            .find_map(|(name, expr)| (name == ident).then_some(expr))
+    }
+
+    /// Returns `true` if any field has an initial value, in which case `Default` is implemented
+    /// by hand instead of derived.
+    pub fn has_default_values(&self) -> bool {
+        !self.defaults.is_empty()
+    }
+
+    /// Returns `true` if the state opted in to the `paused` field.
+    pub fn is_pausable(&self) -> bool {
+        self.pausable
+    }
+
+    /// Returns the schema version given with `#[fvm_macro(state, version = N)]`, which is stored
+    /// together with the state root.
+    pub fn version(&self) -> Option<u64> {
+        self.version
+    }
+
+    /// Returns how the state is serialized, tuple encoded unless set otherwise.
+    pub fn encoding(&self) -> attrs::Encoding {
+        self.encoding
     }
 
-    pub fn fields(&self) -> syn::punctuated::Iter<syn::Field> {
-        self.ast.fields.iter()
+    /// Returns `true` if the state declares a field with the given name.
+    ///
+    /// The fields added by components are listed by `ItemMod::component_fields`.
+    pub fn has_field(&self, name: &Ident) -> bool {
+        self.fields().any(|field| field.ident.as_ref() == Some(name))
     }
 }
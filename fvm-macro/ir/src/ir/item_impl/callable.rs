@@ -42,11 +42,7 @@ impl<C> Copy for CallableWithSelector<'_, C> {}
 
 impl<C> Clone for CallableWithSelector<'_, C> {
     fn clone(&self) -> Self {
-        Self {
-            composed_selector: self.composed_selector,
-            item_impl: self.item_impl,
-            callable: self.callable,
-        }
+        *self
     }
 }
 
@@ -105,7 +101,7 @@ impl<'a, C> Callable for CallableWithSelector<'a, C>
         <C as Callable>::visibility(self.callable)
     }
 
-    fn inputs(&self) -> InputsIter {
+    fn inputs(&self) -> InputsIter<'_> {
         <C as Callable>::inputs(self.callable)
     }
 
@@ -139,7 +135,7 @@ pub trait Callable {
 
     fn visibility(&self) -> Visibility;
 
-    fn inputs(&self) -> InputsIter;
+    fn inputs(&self) -> InputsIter<'_>;
 
     fn inputs_span(&self) -> Span;
 
@@ -4,6 +4,7 @@ use proc_macro2::{
 };
 use syn::spanned::Spanned as _;
 use crate::ir::{attrs, attrs::{
+    CallerPolicy,
     SelectorOrWildcard
 }, item_impl::{
     callable
@@ -14,6 +15,7 @@ pub struct Constructor {
     pub(super) item: syn::ImplItemMethod,
     is_payable: bool,
     selector: Option<SelectorOrWildcard>,
+    caller: CallerPolicy,
 }
 
 impl quote::ToTokens for Constructor {
@@ -34,7 +36,8 @@ impl Constructor {
                 match arg.kind() {
                     attrs::AttributeArg::Constructor
                     | attrs::AttributeArg::Payable
-                    | attrs::AttributeArg::Selector(_) => Ok(()),
+                    | attrs::AttributeArg::Selector(_)
+                    | attrs::AttributeArg::Caller(_) => Ok(()),
                     _ => Err(None),
                 }
             },
@@ -49,9 +52,11 @@ impl TryFrom<syn::ImplItemMethod> for Constructor {
         let (fvm_attrs, other_attrs) = Self::sanitize_attributes(&method_item)?;
         let is_payable = fvm_attrs.is_payable();
         let selector = fvm_attrs.selector();
+        let caller = fvm_attrs.caller_policy().unwrap_or_default();
         Ok(Constructor {
             selector,
             is_payable,
+            caller,
             item: syn::ImplItemMethod {
                 attrs: other_attrs,
                 ..method_item
@@ -95,7 +100,7 @@ impl callable::Callable for Constructor {
         }
     }
 
-    fn inputs(&self) -> callable::InputsIter {
+    fn inputs(&self) -> callable::InputsIter<'_> {
         callable::InputsIter::from(self)
     }
 
@@ -112,4 +117,8 @@ impl Constructor {
     pub fn attrs(&self) -> &[syn::Attribute] {
         &self.item.attrs
     }
+
+    pub fn caller_policy(&self) -> &CallerPolicy {
+        &self.caller
+    }
 }
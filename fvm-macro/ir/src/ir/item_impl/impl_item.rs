@@ -7,6 +7,7 @@ use crate::error::ExtError as _;
 use super::{
     constructor,
     message,
+    migrate,
 };
 
 use crate::{
@@ -21,6 +22,7 @@ use syn::spanned::Spanned as _;
 pub enum ImplItem {
     Constructor(constructor::Constructor),
     Message(message::Message),
+    Migrate(migrate::Migrate),
     Other(syn::ImplItem),
 }
 
@@ -29,6 +31,7 @@ impl quote::ToTokens for ImplItem {
         match self {
             Self::Constructor(constructor) => constructor.to_tokens(tokens),
             Self::Message(message) => message.to_tokens(tokens),
+            Self::Migrate(migrate) => migrate.to_tokens(tokens),
             Self::Other(other) => other.to_tokens(tokens),
         }
     }
@@ -49,18 +52,27 @@ impl TryFrom<syn::ImplItem> for ImplItem {
                 match attr.first().kind() {
                     attrs::AttributeArg::Message => {
                         <message::Message as TryFrom<_>>::try_from(method_item)
-                            .map(Into::into)
                             .map(Self::Message)
                     }
                     attrs::AttributeArg::Constructor => {
                         <constructor::Constructor as TryFrom<_>>::try_from(method_item)
-                            .map(Into::into)
                             .map(Self::Constructor)
                     }
+                    // Method 0 sends value without invoking the actor, there is nothing to hook.
+                    attrs::AttributeArg::Receive => Err(format_err_spanned!(
+                        method_item,
+                        "fvm receive hooks are not supported, the FVM transfers the value sent with \
+                        method 0 without invoking the actor"
+                    )),
+                    attrs::AttributeArg::Migrate(_) => {
+                        <migrate::Migrate as TryFrom<_>>::try_from(method_item)
+                            .map(Self::Migrate)
+                    }
                     _ => Err(format_err_spanned!(
                         method_item,
                         "encountered invalid fvm attribute at this point, expected either \
-                        #[fvm(message)] or #[fvm(constructor) attributes"
+                        #[fvm(message)], #[fvm(constructor)] or \
+                        #[fvm(migrate(from = N))] attributes"
                     )),
                 }
             }
@@ -109,6 +121,13 @@ impl ImplItem {
         }
     }
 
+    pub fn filter_map_migrate(&self) -> Option<&migrate::Migrate> {
+        match self {
+            ImplItem::Migrate(migrate) => Some(migrate),
+            _ => None,
+        }
+    }
+
     pub fn is_other_item(&self) -> bool {
         self.filter_map_other_item().is_some()
     }
@@ -1,3 +1,4 @@
+use crate::format_err_spanned;
 use crate::ir::{
     item_impl::{
         callable,
@@ -45,6 +46,10 @@ pub struct Message {
     pub(super) item: syn::ImplItemMethod,
     is_payable: bool,
     selector: Option<SelectorOrWildcard>,
+    only: Option<Ident>,
+    roles: Vec<Ident>,
+    when_not_paused: bool,
+    nonreentrant: bool,
 }
 
 impl quote::ToTokens for Message {
@@ -65,7 +70,11 @@ impl Message {
                 match arg.kind() {
                     attrs::AttributeArg::Message
                     | attrs::AttributeArg::Payable
-                    | attrs::AttributeArg::Selector(_) => Ok(()),
+                    | attrs::AttributeArg::Selector(_)
+                    | attrs::AttributeArg::Only(_)
+                    | attrs::AttributeArg::Roles(_)
+                    | attrs::AttributeArg::WhenNotPaused
+                    | attrs::AttributeArg::Nonreentrant => Ok(()),
                     _ => Err(None),
                 }
             },
@@ -73,6 +82,81 @@ impl Message {
     }
 }
 
+impl Message {
+    /// Ensures that an `Env` argument is only declared as `&Env` in the first position.
+    fn ensure_env_first(method_item: &syn::ImplItemMethod) -> Result<(), syn::Error> {
+        let misplaced = method_item
+            .sig
+            .inputs
+            .iter()
+            .filter_map(|arg| match arg {
+                syn::FnArg::Typed(pat_type) => Some(pat_type),
+                syn::FnArg::Receiver(_) => None,
+            })
+            .enumerate()
+            .find(|(index, pat_type)| {
+                mentions_env(&pat_type.ty) && (*index != 0 || !is_env_ref(&pat_type.ty))
+            });
+        if let Some((_, pat_type)) = misplaced {
+            return Err(format_err_spanned!(
+                pat_type,
+                "the invocation context must be the first argument of a message and of type `&Env`",
+            ));
+        }
+        Ok(())
+    }
+}
+
+impl Message {
+    /// Ensures that a fallback message takes the method number and the raw params, after the
+    /// optional invocation context.
+    fn ensure_fallback_inputs(method_item: &syn::ImplItemMethod) -> Result<(), syn::Error> {
+        let params = method_item
+            .sig
+            .inputs
+            .iter()
+            .filter(|arg| match arg {
+                syn::FnArg::Typed(pat_type) => !is_env_ref(&pat_type.ty),
+                syn::FnArg::Receiver(_) => false,
+            })
+            .count();
+        if params != 2 {
+            return Err(format_err_spanned!(
+                method_item.sig,
+                "fallback messages with `selector = _` must take the method number and the raw params",
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Returns `true` if the type is a shared reference to `Env`.
+pub(super) fn is_env_ref(ty: &syn::Type) -> bool {
+    match ty {
+        syn::Type::Reference(reference) => reference.mutability.is_none() && is_env(&reference.elem),
+        _ => false,
+    }
+}
+
+/// Returns `true` if the type is `Env` or a reference to it.
+fn mentions_env(ty: &syn::Type) -> bool {
+    match ty {
+        syn::Type::Reference(reference) => is_env(&reference.elem),
+        ty => is_env(ty),
+    }
+}
+
+fn is_env(ty: &syn::Type) -> bool {
+    match ty {
+        syn::Type::Path(type_path) => type_path
+            .path
+            .segments
+            .last()
+            .is_some_and(|segment| segment.ident == "Env"),
+        _ => false,
+    }
+}
+
 impl TryFrom<syn::ImplItemMethod> for Message {
     type Error = syn::Error;
 
@@ -80,9 +164,21 @@ impl TryFrom<syn::ImplItemMethod> for Message {
         let (fvm_attrs, other_attrs) = Self::sanitize_attributes(&method_item)?;
         let is_payable = fvm_attrs.is_payable();
         let selector = fvm_attrs.selector();
+        let only = fvm_attrs.only();
+        let roles = fvm_attrs.roles();
+        let when_not_paused = fvm_attrs.is_when_not_paused();
+        let nonreentrant = fvm_attrs.is_nonreentrant();
+        Self::ensure_env_first(&method_item)?;
+        if selector == Some(SelectorOrWildcard::Wildcard) {
+            Self::ensure_fallback_inputs(&method_item)?;
+        }
         Ok(Self {
             is_payable,
             selector,
+            only,
+            roles,
+            when_not_paused,
+            nonreentrant,
             item: syn::ImplItemMethod {
                 attrs: other_attrs,
                 ..method_item
@@ -126,7 +222,7 @@ impl callable::Callable for Message {
         }
     }
 
-    fn inputs(&self) -> callable::InputsIter {
+    fn inputs(&self) -> callable::InputsIter<'_> {
         callable::InputsIter::from(self)
     }
 
@@ -165,6 +261,39 @@ impl Message {
         }
     }
 
+    /// Returns the state field holding the only actor allowed to call this message.
+    pub fn only(&self) -> Option<&Ident> {
+        self.only.as_ref()
+    }
+
+    /// Returns the roles any of which permits a caller to call this message.
+    pub fn roles(&self) -> &[Ident] {
+        &self.roles
+    }
+
+    /// Returns `true` if the message takes the invocation context as its first argument.
+    pub fn has_env(&self) -> bool {
+        self.inputs()
+            .next()
+            .is_some_and(|pat_type| is_env_ref(&pat_type.ty))
+    }
+
+    /// Returns the inputs of the message decoded from the params, leaving out the context.
+    pub fn params(&self) -> core::iter::Skip<callable::InputsIter<'_>> {
+        self.inputs().skip(usize::from(self.has_env()))
+    }
+
+    /// Returns `true` if the message aborts while the contract is paused.
+    pub fn is_when_not_paused(&self) -> bool {
+        self.when_not_paused
+    }
+
+    /// Returns `true` if the message aborts when it is entered while a nonreentrant message of
+    /// the contract is still running.
+    pub fn is_nonreentrant(&self) -> bool {
+        self.nonreentrant
+    }
+
     pub fn local_id(&self) -> u32 {
         utils::local_message_id(self.ident())
     }
@@ -0,0 +1,97 @@
+use crate::format_err_spanned;
+use crate::ir::attrs;
+use proc_macro2::Ident;
+use syn::spanned::Spanned as _;
+
+/// A function upgrading the state from an older schema version, marked with
+/// `#[fvm_macro(migrate(from = N))]`.
+///
+/// The function takes the state as it was stored at version `N` and returns it at version
+/// `N + 1`, so that migrations chain up to the current version of the state.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Migrate {
+    pub(super) item: syn::ImplItemMethod,
+    from: u64,
+}
+
+impl quote::ToTokens for Migrate {
+    fn to_tokens(&self, tokens: &mut proc_macro2::TokenStream) {
+        self.item.to_tokens(tokens)
+    }
+}
+
+impl Migrate {
+    fn sanitize_attributes(
+        method_item: &syn::ImplItemMethod,
+    ) -> Result<(attrs::FvmAttribute, Vec<syn::Attribute>), syn::Error> {
+        attrs::sanitize_attributes(
+            method_item.span(),
+            method_item.attrs.clone(),
+            &attrs::AttributeArgKind::Migrate,
+            |arg| {
+                match arg.kind() {
+                    attrs::AttributeArg::Migrate(_) => Ok(()),
+                    _ => Err(None),
+                }
+            },
+        )
+    }
+
+    fn ensure_valid_signature(method_item: &syn::ImplItemMethod) -> Result<(), syn::Error> {
+        let sig = &method_item.sig;
+        let mut inputs = sig.inputs.iter();
+        match (inputs.next(), inputs.next()) {
+            (Some(syn::FnArg::Typed(_)), None) => {}
+            _ => {
+                return Err(format_err_spanned!(
+                    sig,
+                    "fvm migrations must take the old state as their only argument",
+                ))
+            }
+        }
+        if let syn::ReturnType::Default = sig.output {
+            return Err(format_err_spanned!(
+                sig,
+                "fvm migrations must return the state of the next version",
+            ));
+        }
+        Ok(())
+    }
+}
+
+impl TryFrom<syn::ImplItemMethod> for Migrate {
+    type Error = syn::Error;
+
+    fn try_from(method_item: syn::ImplItemMethod) -> Result<Self, Self::Error> {
+        let (fvm_attrs, other_attrs) = Self::sanitize_attributes(&method_item)?;
+        Self::ensure_valid_signature(&method_item)?;
+        Ok(Self {
+            from: fvm_attrs
+                .migrate_from()
+                .expect("checked to be a migration when sanitizing the attributes"),
+            item: syn::ImplItemMethod {
+                attrs: other_attrs,
+                ..method_item
+            },
+        })
+    }
+}
+
+impl Migrate {
+    pub fn ident(&self) -> &Ident {
+        &self.item.sig.ident
+    }
+
+    /// Returns the state version the function migrates from.
+    pub fn from(&self) -> u64 {
+        self.from
+    }
+
+    /// Returns the type of the state at the version the function migrates from.
+    pub fn input(&self) -> &syn::Type {
+        match self.item.sig.inputs.first() {
+            Some(syn::FnArg::Typed(pat_type)) => &pat_type.ty,
+            _ => unreachable!("checked to take the old state when parsing"),
+        }
+    }
+}
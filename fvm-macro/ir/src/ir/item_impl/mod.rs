@@ -2,6 +2,7 @@ pub mod impl_item;
 pub mod constructor;
 pub mod callable;
 pub mod message;
+pub mod migrate;
 pub mod iter;
 
 use super::{
@@ -21,6 +22,9 @@ use syn::spanned::Spanned as _;
 use quote::TokenStreamExt as _;
 use crate::error::ExtError as _;
 
+/// The namespaces of the messages the built-in components add, whose method numbers a
+/// namespaced impl block with the same name would collide with.
+const RESERVED_NAMESPACES: [&str; 5] = ["Ownable", "Pausable", "Rbac", "Migration", "Batch"];
 
 #[derive(Debug, PartialEq, Eq)]
 pub struct ItemImpl {
@@ -88,12 +92,6 @@ impl TryFrom<syn::ItemImpl> for ItemImpl {
                 "unsafe fvm implementation blocks are not supported",
             ));
         }
-        if !item_impl.generics.params.is_empty() {
-            return Err(format_err_spanned!(
-                item_impl.generics.params,
-                "generic fvm implementation blocks are not supported",
-            ));
-        }
         let impl_items = item_impl
             .items
             .into_iter()
@@ -123,6 +121,15 @@ impl TryFrom<syn::ItemImpl> for ItemImpl {
                 "namespace fvm property is not allowed on fvm trait implementation blocks",
             ));
         }
+        if let Some(namespace) = namespace.as_ref().filter(|namespace| {
+            RESERVED_NAMESPACES.contains(&namespace.as_str())
+        }) {
+            return Err(format_err!(
+                impl_block_span,
+                "the `{}` namespace is reserved for the messages of the built-in components",
+                namespace.as_str(),
+            ));
+        }
         Ok(Self {
             attrs: other_attrs,
             defaultness: item_impl.defaultness,
@@ -177,7 +184,10 @@ impl ItemImpl {
                     let attr = attrs::first_fvm_attribute(&method_item.attrs)?
                         .expect("missing expected fvm attribute for struct");
                     match attr.first().kind() {
-                        attrs::AttributeArg::Constructor | attrs::AttributeArg::Message => {
+                        attrs::AttributeArg::Constructor
+                        | attrs::AttributeArg::Message
+                        | attrs::AttributeArg::Receive
+                        | attrs::AttributeArg::Migrate(_) => {
                             return Ok(true);
                         }
                         _ => continue 'repeat,
@@ -195,6 +205,10 @@ impl ItemImpl {
         &self.attrs
     }
 
+    pub fn generics(&self) -> &syn::Generics {
+        &self.generics
+    }
+
     pub fn self_type(&self) -> &syn::Type {
         self.self_ty.as_ref()
     }
@@ -207,14 +221,18 @@ impl ItemImpl {
         self.namespace.as_ref()
     }
 
-    pub fn iter_messages(&self) -> IterMessages {
+    pub fn iter_messages(&self) -> IterMessages<'_> {
         IterMessages::new(self)
     }
 
-    pub fn iter_constructors(&self) -> IterConstructors {
+    pub fn iter_constructors(&self) -> IterConstructors<'_> {
         IterConstructors::new(self)
     }
 
+    pub fn iter_migrations(&self) -> impl Iterator<Item = &migrate::Migrate> {
+        self.items.iter().filter_map(impl_item::ImplItem::filter_map_migrate)
+    }
+
     pub fn items(&self) -> &[impl_item::ImplItem] {
         &self.items
     }
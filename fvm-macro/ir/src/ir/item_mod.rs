@@ -1,12 +1,26 @@
 use crate::ir::{
     item_impl,
+    item_impl::{
+        callable::{
+            Callable as _,
+            CallableWithSelector,
+        },
+        constructor::Constructor,
+        message::Message,
+        migrate::Migrate,
+    },
+    selector::Selector,
     item,
     attrs,
+    config,
 };
 
 use proc_macro2::{Ident};
 
-use crate::format_err_spanned;
+use crate::{
+    error::ExtError as _,
+    format_err_spanned,
+};
 
 use syn::{
     token,
@@ -42,6 +56,9 @@ impl TryFrom<syn::ItemMod> for ItemMod {
             .map(<item::Item as TryFrom<syn::Item>>::try_from)
             .collect::<Result<Vec<_>, syn::Error>>()?;
 
+        Self::ensure_single_constructor(&items)?;
+        Self::ensure_single_fallback(&items)?;
+
         Ok(Self {
             attrs: other_attrs,
             vis: module.vis,
@@ -54,6 +71,412 @@ impl TryFrom<syn::ItemMod> for ItemMod {
 }
 
 
+impl ItemMod {
+    fn ensure_single_constructor(items: &[item::Item]) -> Result<(), syn::Error> {
+        let mut constructors = items
+            .iter()
+            .filter_map(item::Item::map_fvm_item)
+            .filter_map(item::FvmItem::filter_map_impl_block)
+            .flat_map(item_impl::ItemImpl::iter_constructors);
+        let _ = constructors.next();
+        if let Some(duplicate) = constructors.next() {
+            return Err(format_err_spanned!(
+                duplicate.callable(),
+                "encountered multiple fvm constructors, at most one is supported",
+            ));
+        }
+        Ok(())
+    }
+}
+
+
+impl ItemMod {
+    fn ensure_single_fallback(items: &[item::Item]) -> Result<(), syn::Error> {
+        let mut fallbacks = items
+            .iter()
+            .filter_map(item::Item::map_fvm_item)
+            .filter_map(item::FvmItem::filter_map_impl_block)
+            .flat_map(item_impl::ItemImpl::iter_messages)
+            .filter(|cws| cws.has_wildcard_selector());
+        let _ = fallbacks.next();
+        if let Some(duplicate) = fallbacks.next() {
+            return Err(format_err_spanned!(
+                duplicate.callable(),
+                "encountered multiple fvm messages with wildcard selectors, at most one is supported",
+            ));
+        }
+        Ok(())
+    }
+}
+
+
+impl ItemMod {
+    /// Ensures that every state field named by an access rule of a message exists.
+    ///
+    /// With the built-in role-based access control enabled `roles` names roles instead of fields.
+    pub(crate) fn ensure_access_fields(&self, config: &config::Config) -> Result<(), syn::Error> {
+        let state = match IterFvmItems::new(self).find_map(item::FvmItem::filter_map_storage_item) {
+            Some(state) => state,
+            None => return Ok(()),
+        };
+        let mut err: Option<syn::Error> = None;
+        let fields = self
+            .impls()
+            .flat_map(item_impl::ItemImpl::iter_messages)
+            .flat_map(|cws| {
+                let roles = if config.rbac() { &[][..] } else { cws.callable().roles() };
+                cws.callable().only().into_iter().chain(roles)
+            });
+        let component_fields = self.component_fields(config);
+        for field in fields {
+            if state.has_field(field) || component_fields.iter().any(|(name, _)| field == name) {
+                continue;
+            }
+            let missing = format_err_spanned!(
+                field,
+                "no field `{}` in fvm state struct `{}`",
+                field,
+                state.ident(),
+            );
+            match &mut err {
+                Some(err) => err.combine(missing),
+                None => err = Some(missing),
+            }
+        }
+        match err {
+            Some(err) => Err(err),
+            None => Ok(()),
+        }
+    }
+
+    /// Ensures that no field of the state has the name of a field added by an enabled component.
+    pub(crate) fn ensure_component_fields(&self, config: &config::Config) -> Result<(), syn::Error> {
+        let state = match IterFvmItems::new(self).find_map(item::FvmItem::filter_map_storage_item) {
+            Some(state) => state,
+            None => return Ok(()),
+        };
+        let component_fields = self.component_fields(config);
+        let mut err: Option<syn::Error> = None;
+        for ident in state.fields().filter_map(|field| field.ident.as_ref()) {
+            let component = match component_fields.iter().find(|(name, _)| ident == name) {
+                Some((_, component)) => component,
+                None => continue,
+            };
+            let clash = format_err_spanned!(
+                ident,
+                "the field `{}` clashes with the field of the same name added by `{}`",
+                ident,
+                component,
+            );
+            match &mut err {
+                Some(err) => err.combine(clash),
+                None => err = Some(clash),
+            }
+        }
+        match err {
+            Some(err) => Err(err),
+            None => Ok(()),
+        }
+    }
+
+    /// Returns the names of the fields the enabled components add to the state, each with the
+    /// attribute argument enabling the component.
+    pub fn component_fields(&self, config: &config::Config) -> Vec<(&'static str, &'static str)> {
+        let state = IterFvmItems::new(self).find_map(item::FvmItem::filter_map_storage_item);
+        let mut fields = Vec::new();
+        if state.is_some_and(|state| state.is_ownable()) {
+            fields.extend([("owner", "ownable"), ("pending_owner", "ownable")]);
+        }
+        if state.is_some_and(|state| state.is_pausable()) {
+            fields.push(("paused", "pausable"));
+        }
+        if config.rbac() {
+            fields.push(("rbac", "rbac"));
+        }
+        let nonreentrant = self
+            .impls()
+            .flat_map(item_impl::ItemImpl::iter_messages)
+            .any(|cws| cws.callable().is_nonreentrant());
+        if nonreentrant {
+            fields.push(("reentrancy_lock", "nonreentrant"));
+        }
+        fields
+    }
+
+    /// Ensures that a generic state is instantiated with a concrete type of the state for the
+    /// `invoke` export, and that only a generic state is.
+    pub(crate) fn ensure_instantiate(&self, config: &config::Config) -> Result<(), syn::Error> {
+        let state = match IterFvmItems::new(self).find_map(item::FvmItem::filter_map_storage_item) {
+            Some(state) => state,
+            None => return Ok(()),
+        };
+        let is_generic = !state.generics().params.is_empty();
+        match config.instantiate() {
+            None if is_generic => Err(format_err_spanned!(
+                state.generics().params,
+                "generic fvm states must be instantiated with `#[fvm_macro::contract(instantiate = \"{}<...>\")]`",
+                state.ident(),
+            )),
+            None => Ok(()),
+            Some(ty) if !is_generic => Err(format_err_spanned!(
+                ty,
+                "`instantiate` requires a generic fvm state",
+            )),
+            Some(ty) => {
+                let ident = match ty {
+                    syn::Type::Path(ty) if ty.qself.is_none() => ty.path.segments.last().map(|segment| &segment.ident),
+                    _ => None,
+                };
+                if ident != Some(state.ident()) {
+                    return Err(format_err_spanned!(
+                        ty,
+                        "expected an instance of the fvm state `{}`",
+                        state.ident(),
+                    ));
+                }
+                Ok(())
+            }
+        }
+    }
+
+    /// Ensures that a state without named fields is not combined with components adding fields
+    /// to it, and that an enum or `no_derive` state has a `Default` for the generated constructor
+    /// if it has no constructor of its own, or for the missing fields of its map encoding.
+    pub(crate) fn ensure_state_shape(&self, config: &config::Config) -> Result<(), syn::Error> {
+        let state = match IterFvmItems::new(self).find_map(item::FvmItem::filter_map_storage_item) {
+            Some(state) => state,
+            None => return Ok(()),
+        };
+        if !state.has_named_fields() {
+            if config.rbac() {
+                return Err(format_err_spanned!(
+                    state.ident(),
+                    "the `rbac` contract argument requires a state struct with named fields",
+                ));
+            }
+            let nonreentrant = self
+                .impls()
+                .flat_map(item_impl::ItemImpl::iter_messages)
+                .find(|cws| cws.callable().is_nonreentrant());
+            if let Some(message) = nonreentrant {
+                return Err(format_err_spanned!(
+                    message.ident(),
+                    "`nonreentrant` requires a state struct with named fields",
+                ));
+            }
+        }
+        let is_enum = matches!(state.item(), item::state::StateItem::Enum(_));
+        let has_default = (!is_enum && !state.is_no_derive())
+            || state.has_default_values()
+            || state.derives_default()
+            || self.implements_default(state.ident());
+        if has_default {
+            return Ok(());
+        }
+        if self.constructor().is_none() {
+            let kind = if is_enum { "enum fvm states" } else { "fvm states with `no_derive`" };
+            return Err(format_err_spanned!(
+                state.ident(),
+                "{} need `Default` to be constructed, derive or implement it, or add a \
+                `#[fvm_macro(constructor)]`",
+                kind,
+            ));
+        }
+        if state.encoding() == attrs::Encoding::Map {
+            return Err(format_err_spanned!(
+                state.ident(),
+                "map encoded fvm states with `no_derive` need `Default` for their missing fields, \
+                derive or implement it",
+            ));
+        }
+        Ok(())
+    }
+
+    /// Returns `true` if the module contains `impl Default for #ident`.
+    fn implements_default(&self, ident: &Ident) -> bool {
+        self.items
+            .iter()
+            .filter_map(item::Item::map_rust_item)
+            .any(|item| match item {
+                syn::Item::Impl(item_impl) => {
+                    let is_default = item_impl.trait_.as_ref().is_some_and(|(_, path, _)| {
+                        path.segments.last().is_some_and(|segment| segment.ident == "Default")
+                    });
+                    let is_state = matches!(&*item_impl.self_ty, syn::Type::Path(ty) if ty.path.is_ident(ident));
+                    is_default && is_state
+                }
+                _ => false,
+            })
+    }
+
+    /// Ensures that `when_not_paused` is only used with a pausable state, and that a pausable
+    /// state has someone allowed to pause it: its owner or a member of the pauser role.
+    pub(crate) fn ensure_pausable(&self, config: &config::Config) -> Result<(), syn::Error> {
+        let state = IterFvmItems::new(self).find_map(item::FvmItem::filter_map_storage_item);
+        let pausable = state.is_some_and(|state| state.is_pausable());
+        if let Some(state) = state.filter(|state| state.is_pausable()) {
+            if !state.is_ownable() && !config.rbac() {
+                return Err(format_err_spanned!(
+                    state.ident(),
+                    "pausable fvm state structs must be `ownable` or use the `rbac` contract argument",
+                ));
+            }
+        }
+        let unguarded = self
+            .impls()
+            .flat_map(item_impl::ItemImpl::iter_messages)
+            .find(|cws| cws.callable().is_when_not_paused() && !pausable);
+        if let Some(message) = unguarded {
+            return Err(format_err_spanned!(
+                message.ident(),
+                "`when_not_paused` requires a state struct marked with `#[fvm_macro(state, pausable)]`",
+            ));
+        }
+        Ok(())
+    }
+}
+
+
+impl ItemMod {
+    /// Ensures that the migrations upgrade a versioned state one version at a time, from version
+    /// 0, the layout from before the state was versioned, up to the current one, so that a root
+    /// written at any earlier version can be loaded.
+    pub(crate) fn ensure_migrations(&self) -> Result<(), syn::Error> {
+        let migrations = self.migrations();
+        let state = IterFvmItems::new(self).find_map(item::FvmItem::filter_map_storage_item);
+        let version = match state.and_then(|state| state.version()) {
+            Some(version) => version,
+            None => match migrations.first() {
+                Some(oldest) => {
+                    return Err(format_err_spanned!(
+                        oldest.ident(),
+                        "fvm migrations require a state struct marked with `#[fvm_macro(state, version = N)]`",
+                    ))
+                }
+                None => return Ok(()),
+            },
+        };
+        let mut expected = 0;
+        for migration in &migrations {
+            if migration.from() >= version {
+                return Err(format_err_spanned!(
+                    migration.ident(),
+                    "fvm migrations must migrate from a version older than the current version {}",
+                    version,
+                ));
+            }
+            if migration.from() < expected {
+                return Err(format_err_spanned!(
+                    migration.ident(),
+                    "encountered multiple fvm migrations from version {}",
+                    migration.from(),
+                ));
+            }
+            if migration.from() > expected {
+                return Err(format_err_spanned!(
+                    migration.ident(),
+                    "missing fvm migration from version {}",
+                    expected,
+                ));
+            }
+            expected += 1;
+        }
+        if expected != version {
+            return Err(format_err_spanned!(
+                state.expect("checked to be versioned above").ident(),
+                "missing fvm migration from version {}",
+                expected,
+            ));
+        }
+        Ok(())
+    }
+}
+
+
+impl ItemMod {
+    /// Ensures that no two messages share a composed selector or a method number, and that no
+    /// message takes the method number of a message generated by a component.
+    ///
+    /// This covers messages of different impl blocks, including trait impls and namespaced
+    /// impls, and user provided selectors. Each collision is reported at both messages.
+    pub(crate) fn ensure_no_overlapping_selectors(&self, config: &config::Config) -> Result<(), syn::Error> {
+        let mut err: Option<syn::Error> = None;
+        let mut push = |error: syn::Error| {
+            err = Some(match err.take() {
+                Some(err) => err.into_combine(error),
+                None => error,
+            })
+        };
+        let messages = self
+            .impls()
+            .flat_map(item_impl::ItemImpl::iter_messages)
+            .filter(|cws| !cws.has_wildcard_selector())
+            .collect::<Vec<_>>();
+        for (index, cws) in messages.iter().enumerate() {
+            let selector = cws.composed_selector();
+            if let Some(first) = messages[..index]
+                .iter()
+                .find(|first| first.composed_selector() == selector)
+            {
+                push(
+                    format_err_spanned!(
+                        cws.callable(),
+                        "encountered fvm messages with overlapping selectors (= {:02X?})",
+                        selector.to_bytes(),
+                    )
+                    .into_combine(format_err_spanned!(
+                        first.callable(),
+                        "first fvm message with overlapping selector here",
+                    )),
+                );
+            }
+        }
+        let numbered = self.dispatched_messages();
+        for (method_number, cws) in numbered.iter().filter(|(method_number, _)| *method_number < 2) {
+            push(format_err_spanned!(
+                cws.callable(),
+                "encountered fvm message with the method number {}, which is reserved for the {}",
+                method_number,
+                if *method_number == 0 { "value transfers" } else { "constructor" },
+            ));
+        }
+        for (index, (method_number, cws)) in numbered.iter().enumerate() {
+            if let Some((_, first)) = numbered[..index]
+                .iter()
+                .find(|(first, _)| first == method_number)
+            {
+                push(
+                    format_err_spanned!(
+                        cws.callable(),
+                        "encountered fvm messages with overlapping method numbers (= {})",
+                        method_number,
+                    )
+                    .into_combine(format_err_spanned!(
+                        first.callable(),
+                        "first fvm message with overlapping method number here",
+                    )),
+                );
+            }
+        }
+        let components = self.component_messages(config);
+        for (method_number, cws) in &numbered {
+            if let Some(name) = components
+                .iter()
+                .find(|name| Selector::compute(name.as_bytes()).into_be_u32() as u64 == *method_number)
+            {
+                push(format_err_spanned!(
+                    cws.callable(),
+                    "encountered fvm message with the method number {} of the generated `{}` message",
+                    method_number,
+                    name,
+                ));
+            }
+        }
+        err.map_or(Ok(()), Err)
+    }
+}
+
+
 impl ItemMod {
     pub fn ident(&self) -> &Ident {
         &self.ident
@@ -78,15 +501,102 @@ impl ItemMod {
         storage
     }
 
+    pub fn constructor(&self) -> Option<CallableWithSelector<'_, Constructor>> {
+        self.impls()
+            .flat_map(item_impl::ItemImpl::iter_constructors)
+            .next()
+    }
+
+    /// Returns the message with the wildcard selector, which handles unknown method numbers.
+    pub fn fallback(&self) -> Option<CallableWithSelector<'_, Message>> {
+        self.impls()
+            .flat_map(item_impl::ItemImpl::iter_messages)
+            .find(|cws| cws.has_wildcard_selector())
+    }
+
+    /// Returns the messages dispatched by method number together with their method numbers.
+    ///
+    /// The messages of the inherent impl blocks without a namespace are numbered from 2 in
+    /// declaration order, across all of these blocks. The messages of a namespaced impl block are
+    /// numbered by their selector, composed of the namespace and the message name like the
+    /// component messages, so that several namespaces may be combined in one contract. A message
+    /// with a user provided selector is numbered by it and skipped by the sequential numbering.
+    /// The message with the wildcard selector handles the remaining method numbers and is left
+    /// out.
+    pub fn dispatched_messages(&self) -> Vec<(u64, CallableWithSelector<'_, Message>)> {
+        let mut next_method_number = 2;
+        self.impls()
+            .filter(|item_impl| item_impl.trait_path().is_none())
+            .flat_map(|item_impl| {
+                let namespaced = item_impl.namespace().is_some();
+                item_impl
+                    .iter_messages()
+                    .filter(|cws| !cws.has_wildcard_selector())
+                    .map(move |cws| (namespaced, cws))
+            })
+            .map(|(namespaced, cws)| {
+                if namespaced || cws.callable().user_provided_selector().is_some() {
+                    (cws.composed_selector().into_be_u32() as u64, cws)
+                } else {
+                    next_method_number += 1;
+                    (next_method_number - 1, cws)
+                }
+            })
+            .collect()
+    }
+
+    /// Returns the `Component::message` names of the messages the enabled components generate,
+    /// which are numbered by the selector of their name.
+    pub fn component_messages(&self, config: &config::Config) -> Vec<&'static str> {
+        let mut messages = Vec::new();
+        if self.state().is_ownable() {
+            messages.extend([
+                "Ownable::owner",
+                "Ownable::transfer_ownership",
+                "Ownable::accept_ownership",
+                "Ownable::renounce_ownership",
+            ]);
+        }
+        if self.state().is_pausable() {
+            messages.extend(["Pausable::paused", "Pausable::pause", "Pausable::unpause"]);
+        }
+        if config.rbac() {
+            messages.extend([
+                "Rbac::grant_role",
+                "Rbac::revoke_role",
+                "Rbac::renounce_role",
+                "Rbac::set_role_admin",
+                "Rbac::has_role",
+            ]);
+        }
+        if !self.migrations().is_empty() {
+            messages.push("Migration::migrate");
+        }
+        if config.batch() {
+            messages.push("Batch::batch");
+        }
+        messages
+    }
+
+    /// Returns the migrations of the state, ordered by the version they migrate from.
+    pub fn migrations(&self) -> Vec<&Migrate> {
+        let mut migrations = self
+            .impls()
+            .flat_map(item_impl::ItemImpl::iter_migrations)
+            .collect::<Vec<_>>();
+        migrations.sort_by_key(|migration| migration.from());
+        migrations
+    }
+
     pub fn items(&self) -> &[item::Item] {
         self.items.as_slice()
     }
 
-    pub fn impls(&self) -> IterItemImpls {
+    pub fn impls(&self) -> IterItemImpls<'_> {
         IterItemImpls::new(self)
     }
 
-    pub fn events(&self) -> IterEvents {
+    pub fn events(&self) -> IterEvents<'_> {
         IterEvents::new(self)
     }
 
@@ -2,6 +2,7 @@ pub mod item;
 pub mod item_impl;
 pub mod item_mod;
 pub mod contract;
+pub mod config;
 pub mod attrs;
 pub mod blake2;
 pub mod selector;
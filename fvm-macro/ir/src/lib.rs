@@ -2,6 +2,8 @@ pub mod ir;
 mod error;
 mod literal;
 
+pub use literal::HexLiteral;
+
 #[cfg(test)]
 mod tests {
     #[test]
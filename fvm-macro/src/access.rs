@@ -0,0 +1,85 @@
+use std::collections::{BTreeSet, HashSet};
+
+use fvm_ipld_encoding::tuple::{Deserialize_tuple, Serialize_tuple};
+use fvm_ipld_encoding::RawBytes;
+use fvm_sdk as sdk;
+use fvm_shared::address::Address;
+use fvm_shared::ActorID;
+
+use crate::ownable::INIT_ACTOR_ID;
+use crate::util::{decode_params, resolve_address};
+
+/// A state value naming the single actor allowed to call a message.
+///
+/// Used by `#[fvm_macro(message, only = "field")]`.
+pub trait Principal {
+    fn is_caller(&self, caller: ActorID) -> bool;
+}
+
+impl Principal for ActorID {
+    fn is_caller(&self, caller: ActorID) -> bool {
+        *self == caller
+    }
+}
+
+impl Principal for Address {
+    fn is_caller(&self, caller: ActorID) -> bool {
+        match self.id() {
+            Ok(id) => id == caller,
+            Err(_) => sdk::actor::resolve_address(self) == Some(caller),
+        }
+    }
+}
+
+impl<T: Principal> Principal for Option<T> {
+    fn is_caller(&self, caller: ActorID) -> bool {
+        self.as_ref().is_some_and(|principal| principal.is_caller(caller))
+    }
+}
+
+/// A state value holding the members of a role.
+///
+/// Used by `#[fvm_macro(message, roles = "field")]`.
+pub trait Members {
+    fn contains_caller(&self, caller: ActorID) -> bool;
+}
+
+impl<T: Principal> Members for Vec<T> {
+    fn contains_caller(&self, caller: ActorID) -> bool {
+        self.iter().any(|member| member.is_caller(caller))
+    }
+}
+
+impl<T: Principal> Members for BTreeSet<T> {
+    fn contains_caller(&self, caller: ActorID) -> bool {
+        self.iter().any(|member| member.is_caller(caller))
+    }
+}
+
+impl<T: Principal> Members for HashSet<T> {
+    fn contains_caller(&self, caller: ActorID) -> bool {
+        self.iter().any(|member| member.is_caller(caller))
+    }
+}
+
+/// The params of the generated constructor naming the initial account, i.e. the first owner,
+/// admin and pauser of a contract constructed by the init actor.
+#[derive(Serialize_tuple, Deserialize_tuple, Clone, Debug, PartialEq, Eq)]
+pub struct ConstructorParams {
+    pub account: Address,
+}
+
+/// Returns the account the components of a freshly constructed contract are initialised with.
+///
+/// That is the caller of the constructor, unless the contract is constructed by the init actor.
+/// The account is then taken from the [`ConstructorParams`] in `params`, and is `None` if there
+/// are no params or they belong to the user's constructor.
+pub fn initial_account(params: Option<&RawBytes>) -> Option<ActorID> {
+    let caller = sdk::message::caller();
+    if caller != INIT_ACTOR_ID {
+        return Some(caller);
+    }
+    let params = params.filter(|params| !params.bytes().is_empty())?;
+    let params: ConstructorParams = decode_params(params);
+    Some(resolve_address(&params.account))
+}
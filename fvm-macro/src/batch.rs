@@ -0,0 +1,31 @@
+//! The `batch` message, enabled with `#[fvm_macro::contract(batch)]`.
+//!
+//! A batch runs several messages of the contract against a single loaded state, which is saved
+//! once afterwards. A failing step aborts the invocation, reverting the whole batch.
+
+use fvm_ipld_encoding::RawBytes;
+use fvm_shared::{MethodNum, METHOD_CONSTRUCTOR};
+
+use crate::abort;
+use crate::util::{decode_params, encode_return};
+
+/// Runs the `(method_num, params)` pairs of `params` through `dispatch` in order and returns the
+/// array of their return values, empty for steps returning nothing.
+///
+/// The constructor and nested batches are rejected with `USR_ILLEGAL_ARGUMENT`.
+pub fn run<S>(
+    state: &mut S,
+    params: &RawBytes,
+    batch_method: MethodNum,
+    dispatch: fn(&mut S, MethodNum, RawBytes) -> Option<RawBytes>,
+) -> Option<RawBytes> {
+    let calls: Vec<(MethodNum, RawBytes)> = decode_params(params);
+    let mut results = Vec::with_capacity(calls.len());
+    for (method, params) in calls {
+        if method == METHOD_CONSTRUCTOR || method == batch_method {
+            abort!(USR_ILLEGAL_ARGUMENT, "method {} cannot be batched", method);
+        }
+        results.push(dispatch(state, method, params).unwrap_or_default());
+    }
+    encode_return(results)
+}
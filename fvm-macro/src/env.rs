@@ -0,0 +1,63 @@
+//! The invocation context of a message.
+//!
+//! Messages may declare `env: &Env` as their first argument to read the context from a value
+//! instead of calling the syscalls directly. The dispatcher builds it with
+//! [`Env::from_syscalls`], tests can build it with `Env::mock` under the `testing` feature.
+
+use fvm_sdk as sdk;
+use fvm_shared::clock::ChainEpoch;
+use fvm_shared::econ::TokenAmount;
+use fvm_shared::{ActorID, MethodNum};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Env {
+    /// The immediate caller of the message.
+    pub caller: ActorID,
+    /// The ID of the contract itself.
+    pub receiver: ActorID,
+    /// The funds sent along with the message.
+    pub value: TokenAmount,
+    pub epoch: ChainEpoch,
+    pub method_number: MethodNum,
+    /// The balance of the contract, including `value`.
+    pub balance: TokenAmount,
+}
+
+impl Env {
+    /// Reads the context of the current invocation.
+    pub fn from_syscalls() -> Self {
+        Self {
+            caller: sdk::message::caller(),
+            receiver: sdk::message::receiver(),
+            value: sdk::message::value_received(),
+            epoch: sdk::network::curr_epoch(),
+            method_number: sdk::message::method_number(),
+            balance: sdk::sself::current_balance(),
+        }
+    }
+}
+
+#[cfg(feature = "testing")]
+impl Env {
+    /// The caller of [`Env::mock`].
+    pub const MOCK_CALLER: ActorID = 100;
+    /// The receiver of [`Env::mock`].
+    pub const MOCK_RECEIVER: ActorID = 1000;
+
+    /// Returns a context for native tests: [`Env::MOCK_CALLER`] calling [`Env::MOCK_RECEIVER`]
+    /// at epoch 0 without funds. Override fields with struct update syntax:
+    ///
+    /// ```ignore
+    /// let env = Env { caller: 101, ..Env::mock() };
+    /// ```
+    pub fn mock() -> Self {
+        Self {
+            caller: Self::MOCK_CALLER,
+            receiver: Self::MOCK_RECEIVER,
+            value: TokenAmount::default(),
+            epoch: 0,
+            method_number: 0,
+            balance: TokenAmount::default(),
+        }
+    }
+}
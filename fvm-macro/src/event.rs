@@ -0,0 +1,45 @@
+use fvm_ipld_encoding::{to_vec, RawBytes};
+use fvm_sdk as sdk;
+use serde::Serialize;
+
+/// An event emitted by a contract.
+///
+/// Implemented by the structs marked with `#[fvm_macro(event)]`. Fields marked with
+/// `#[fvm_macro(topic)]` are reported as topics.
+pub trait Event: Serialize {
+    /// The name of the event, left out of the log of anonymous events.
+    const NAME: &'static str;
+    const ANONYMOUS: bool = false;
+
+    fn topics(&self) -> Vec<RawBytes> {
+        Vec::new()
+    }
+}
+
+/// Records the event in the actor's execution trace.
+///
+/// Events are written as debug log lines of the form `event <name> <topics> <data>`, where topics
+/// and data are hex encoded CBOR. The FVM has no event syscall yet, so this is a debugging aid
+/// only: nothing is recorded while debugging is disabled, as on a production network, and
+/// nothing is persisted.
+pub fn emit<E: Event>(event: &E) {
+    if !sdk::debug::enabled() {
+        return;
+    }
+    let data = match to_vec(event) {
+        Ok(data) => data,
+        Err(err) => crate::abort!(USR_SERIALIZATION, "failed to serialize event: {:?}", err),
+    };
+    let topics = event
+        .topics()
+        .iter()
+        .map(|topic| to_hex(topic.bytes()))
+        .collect::<Vec<_>>()
+        .join(",");
+    let name = if E::ANONYMOUS { "" } else { E::NAME };
+    sdk::debug::log(format!("event {} [{}] {}", name, topics, to_hex(&data)));
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
@@ -0,0 +1,352 @@
+//! Storage layout compatibility checks between contract versions.
+//!
+//! Every contract exposes the layout of its state as the JSON constant `STORAGE_LAYOUT`, which is
+//! also listed under `storage` in its ABI. Diffing the layout of the deployed version against the
+//! new one tells whether the new state struct can still decode the existing state root:
+//!
+//! ```ignore
+//! let old = StorageLayout::from_json(include_str!("../layouts/v1.json"))?;
+//! let new = StorageLayout::from_json(my_contract::STORAGE_LAYOUT)?;
+//! let diff = old.diff(&new);
+//! assert!(diff.is_compatible(), "{}", diff);
+//! ```
+
+use core::fmt;
+
+use serde::{Deserialize, Serialize};
+
+/// How the state struct is encoded in the state root.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Encoding {
+    /// A CBOR array of the field values, decoded by position.
+    Tuple,
+    /// A CBOR map keyed by the field names.
+    Map,
+    /// An enum tagged with its variant name, listing the variants in place of fields.
+    Enum,
+    /// A tuple struct with a single field, encoded as that field.
+    Newtype,
+    /// A struct without fields, encoded as `null`.
+    Unit,
+}
+
+impl fmt::Display for Encoding {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Tuple => write!(f, "tuple"),
+            Self::Map => write!(f, "map"),
+            Self::Enum => write!(f, "enum"),
+            Self::Newtype => write!(f, "newtype"),
+            Self::Unit => write!(f, "unit"),
+        }
+    }
+}
+
+/// A persisted field of the state.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct FieldLayout {
+    pub index: usize,
+    pub name: String,
+    /// The field type as written in the state struct, without whitespace.
+    #[serde(rename = "type")]
+    pub ty: String,
+    #[serde(default)]
+    pub immutable: bool,
+}
+
+/// The storage layout of a state struct, as generated by the contract macro.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct StorageLayout {
+    pub encoding: Encoding,
+    /// The version given with `#[fvm_macro(state, version = N)]`, if any.
+    pub version: Option<u64>,
+    /// The versions the state has `migrate(from = N)` functions for.
+    #[serde(default)]
+    pub migrates_from: Vec<u64>,
+    pub fields: Vec<FieldLayout>,
+}
+
+impl StorageLayout {
+    pub fn from_json(json: &str) -> Result<Self, serde_json::Error> {
+        serde_json::from_str(json)
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).expect("storage layouts always serialize")
+    }
+
+    fn field(&self, name: &str) -> Option<&FieldLayout> {
+        self.fields.iter().find(|field| field.name == name)
+    }
+
+    /// Lists the changes from this layout to the `new` one, flagging those that keep the new
+    /// state struct from decoding a state root written with this layout.
+    pub fn diff(&self, new: &StorageLayout) -> LayoutDiff {
+        let mut changes = Vec::new();
+        if self.encoding != new.encoding {
+            changes.push(LayoutChange::breaking(ChangeKind::EncodingChanged {
+                old: self.encoding,
+                new: new.encoding,
+            }));
+        }
+        for old_field in &self.fields {
+            match new.field(&old_field.name) {
+                None => changes.push(LayoutChange {
+                    // A map ignores unknown keys, a tuple has to decode every element and an enum
+                    // every variant the old root may hold.
+                    breaking: new.encoding != Encoding::Map,
+                    kind: ChangeKind::FieldRemoved { name: old_field.name.clone() },
+                }),
+                Some(new_field) => {
+                    if new_field.ty != old_field.ty {
+                        changes.push(LayoutChange::breaking(ChangeKind::TypeChanged {
+                            name: old_field.name.clone(),
+                            old: old_field.ty.clone(),
+                            new: new_field.ty.clone(),
+                        }));
+                    }
+                    if new_field.index != old_field.index && new.encoding == Encoding::Tuple {
+                        changes.push(LayoutChange::breaking(ChangeKind::FieldMoved {
+                            name: old_field.name.clone(),
+                            old: old_field.index,
+                            new: new_field.index,
+                        }));
+                    }
+                }
+            }
+        }
+        for new_field in &new.fields {
+            if self.field(&new_field.name).is_none() {
+                changes.push(LayoutChange {
+                    // A map falls back to the default of missing fields, a tuple requires them,
+                    // and an old root never holds a new variant.
+                    breaking: new.encoding == Encoding::Tuple,
+                    kind: ChangeKind::FieldAdded { name: new_field.name.clone() },
+                });
+            }
+        }
+        // Like the generated decode, `migrate(from = 0)` only loads unversioned roots: a root in
+        // an envelope at version 0 has no migration.
+        let migrated = new.version != self.version
+            && new.version.is_some()
+            && match self.version {
+                None => new.migrates_from.contains(&0),
+                Some(0) => false,
+                Some(version) => new.migrates_from.contains(&version),
+            };
+        if new.version != self.version {
+            changes.push(LayoutChange {
+                // The envelope of the root changes with the version, so the new state can only
+                // load the old root by migrating it.
+                breaking: !migrated,
+                kind: ChangeKind::VersionChanged {
+                    old: self.version,
+                    new: new.version,
+                },
+            });
+        }
+        LayoutDiff { changes, migrated }
+    }
+}
+
+/// A change between two storage layouts.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ChangeKind {
+    EncodingChanged { old: Encoding, new: Encoding },
+    FieldRemoved { name: String },
+    FieldAdded { name: String },
+    FieldMoved { name: String, old: usize, new: usize },
+    TypeChanged { name: String, old: String, new: String },
+    /// The state version changed, `None` standing for an unversioned state.
+    VersionChanged { old: Option<u64>, new: Option<u64> },
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LayoutChange {
+    pub kind: ChangeKind,
+    /// Whether the change keeps the new state struct from decoding the old state root.
+    pub breaking: bool,
+}
+
+impl LayoutChange {
+    fn breaking(kind: ChangeKind) -> Self {
+        Self { kind, breaking: true }
+    }
+}
+
+impl fmt::Display for LayoutChange {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.breaking {
+            write!(f, "breaking: ")?;
+        }
+        match &self.kind {
+            ChangeKind::EncodingChanged { old, new } => {
+                write!(f, "encoding changed from {} to {}", old, new)
+            }
+            ChangeKind::FieldRemoved { name } => write!(f, "field `{}` removed", name),
+            ChangeKind::FieldAdded { name } => write!(f, "field `{}` added", name),
+            ChangeKind::FieldMoved { name, old, new } => {
+                write!(f, "field `{}` moved from index {} to {}", name, old, new)
+            }
+            ChangeKind::TypeChanged { name, old, new } => {
+                write!(f, "field `{}` changed type from `{}` to `{}`", name, old, new)
+            }
+            ChangeKind::VersionChanged { old, new } => {
+                let version = |version: &Option<u64>| match version {
+                    Some(version) => version.to_string(),
+                    None => "unversioned".to_string(),
+                };
+                write!(f, "version changed from {} to {}", version(old), version(new))
+            }
+        }
+    }
+}
+
+/// The changes between two storage layouts.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LayoutDiff {
+    pub changes: Vec<LayoutChange>,
+    /// Whether the new version migrates state roots of the old version on load, in which case
+    /// the old root does not have to decode with the new layout.
+    pub migrated: bool,
+}
+
+impl LayoutDiff {
+    pub fn breaking_changes(&self) -> impl Iterator<Item = &LayoutChange> {
+        self.changes.iter().filter(|change| change.breaking)
+    }
+
+    /// Returns `true` if the new version can load a state root written by the old one.
+    pub fn is_compatible(&self) -> bool {
+        self.migrated || self.breaking_changes().next().is_none()
+    }
+}
+
+impl fmt::Display for LayoutDiff {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.changes.is_empty() {
+            return write!(f, "storage layouts are identical");
+        }
+        for change in &self.changes {
+            writeln!(f, "{}", change)?;
+        }
+        if self.migrated {
+            write!(f, "the old state is migrated on load")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn layout(encoding: Encoding, fields: &[(&str, &str)]) -> StorageLayout {
+        StorageLayout {
+            encoding,
+            version: None,
+            migrates_from: Vec::new(),
+            fields: fields
+                .iter()
+                .enumerate()
+                .map(|(index, (name, ty))| FieldLayout {
+                    index,
+                    name: name.to_string(),
+                    ty: ty.to_string(),
+                    immutable: false,
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn tuple_changes_are_breaking() {
+        let old = layout(Encoding::Tuple, &[("count", "u64"), ("owner", "Address")]);
+        let new = layout(Encoding::Tuple, &[("owner", "Address"), ("count", "u32")]);
+        let diff = old.diff(&new);
+        assert!(!diff.is_compatible());
+        assert_eq!(diff.breaking_changes().count(), 3);
+    }
+
+    #[test]
+    fn map_tolerates_added_and_removed_fields() {
+        let old = layout(Encoding::Map, &[("count", "u64"), ("label", "String")]);
+        let new = layout(Encoding::Map, &[("count", "u64"), ("step", "u64")]);
+        let diff = old.diff(&new);
+        assert_eq!(diff.changes.len(), 2);
+        assert!(diff.is_compatible());
+    }
+
+    #[test]
+    fn enum_tolerates_added_variants() {
+        let old = layout(Encoding::Enum, &[("Open", "{bid:u64}"), ("Closed", "")]);
+        let new = layout(Encoding::Enum, &[("Open", "{bid:u64}"), ("Cancelled", ""), ("Closed", "")]);
+        assert!(old.diff(&new).is_compatible());
+        assert!(!new.diff(&old).is_compatible());
+    }
+
+    #[test]
+    fn migration_makes_changes_compatible() {
+        let old = layout(Encoding::Tuple, &[("count", "u64")]);
+        let mut new = layout(Encoding::Tuple, &[("count", "u64"), ("step", "u64")]);
+        assert!(!old.diff(&new).is_compatible());
+        new.version = Some(1);
+        new.migrates_from = vec![0];
+        assert!(old.diff(&new).is_compatible());
+    }
+
+    #[test]
+    fn version_bump_without_migration_is_breaking() {
+        let mut old = layout(Encoding::Tuple, &[("count", "u64")]);
+        old.version = Some(1);
+        let mut new = old.clone();
+        new.version = Some(2);
+        new.migrates_from = vec![0];
+        let diff = old.diff(&new);
+        assert!(!diff.is_compatible());
+        assert_eq!(
+            diff.changes,
+            vec![LayoutChange::breaking(ChangeKind::VersionChanged {
+                old: Some(1),
+                new: Some(2),
+            })],
+        );
+        assert_eq!(diff.to_string(), "breaking: version changed from 1 to 2\n");
+
+        new.migrates_from = vec![0, 1];
+        assert!(old.diff(&new).is_compatible());
+    }
+
+    #[test]
+    fn version_zero_is_not_migrated_from_unversioned_roots() {
+        let mut old = layout(Encoding::Tuple, &[("count", "u64")]);
+        old.version = Some(0);
+        let mut new = old.clone();
+        new.version = Some(1);
+        new.migrates_from = vec![0];
+        let diff = old.diff(&new);
+        assert!(!diff.migrated);
+        assert!(!diff.is_compatible());
+        assert_eq!(
+            diff.changes,
+            vec![LayoutChange::breaking(ChangeKind::VersionChanged {
+                old: Some(0),
+                new: Some(1),
+            })],
+        );
+
+        old.version = None;
+        let diff = old.diff(&new);
+        assert!(diff.migrated);
+        assert!(diff.is_compatible());
+    }
+
+    #[test]
+    fn layout_round_trips() {
+        let json = r#"{"encoding":"tuple","fields":[{"index":0,"name":"count","type":"u64"}],"migratesFrom":[],"version":null}"#;
+        let layout = StorageLayout::from_json(json).unwrap();
+        assert_eq!(StorageLayout::from_json(&layout.to_json()).unwrap(), layout);
+    }
+}
@@ -0,0 +1,156 @@
+//! State fields stored in a block of their own and loaded on first access.
+
+use std::cell::OnceCell;
+use std::fmt;
+use std::ops::{Deref, DerefMut};
+
+use cid::multihash::Code;
+use cid::Cid;
+use fvm_ipld_encoding::CborStore;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::{abort, Blockstore};
+
+/// A state field stored as a CID link in the state root and fetched from the [`Blockstore`] only
+/// when dereferenced.
+///
+/// The generated `State::save` calls [`Lazy::flush`] before serializing the state, which writes
+/// the value as a new block if it differs from the stored one. Serializing the field only writes
+/// the link.
+///
+/// ```ignore
+/// #[fvm_macro(state)]
+/// pub struct Registry {
+///     pub metadata: Lazy<Metadata>,
+/// }
+/// ```
+pub struct Lazy<T> {
+    cid: Option<Cid>,
+    value: OnceCell<T>,
+    /// The value stored under `cid`, once loaded or flushed.
+    stored: OnceCell<T>,
+}
+
+impl<T> Lazy<T> {
+    /// Wraps a new value, which is written when the state is saved.
+    pub fn new(value: T) -> Self {
+        Self {
+            cid: None,
+            value: OnceCell::from(value),
+            stored: OnceCell::new(),
+        }
+    }
+
+    /// Returns `true` if the value was loaded or set during this invocation.
+    pub fn is_loaded(&self) -> bool {
+        self.value.get().is_some()
+    }
+
+    /// Returns the link to the stored value, `None` if the value was never written.
+    pub fn cid(&self) -> Option<Cid> {
+        self.cid
+    }
+}
+
+impl<T: DeserializeOwned + Clone> Lazy<T> {
+    fn load(&self) -> &T {
+        self.value.get_or_init(|| {
+            let cid = match self.cid {
+                Some(cid) => cid,
+                None => abort!(USR_ILLEGAL_STATE, "lazy field has neither a value nor a link"),
+            };
+            match Blockstore.get_cbor::<T>(&cid) {
+                Ok(Some(value)) => {
+                    let _ = self.stored.set(value.clone());
+                    value
+                }
+                Ok(None) => abort!(USR_ILLEGAL_STATE, "lazy field {} does not exist", cid),
+                Err(err) => abort!(USR_ILLEGAL_STATE, "failed to get lazy field {}: {}", cid, err),
+            }
+        })
+    }
+}
+
+impl<T: Serialize + Clone + PartialEq> Lazy<T> {
+    /// Writes the value as a new block if it differs from the stored one, keeping the link
+    /// otherwise. A value that was never loaded is left untouched.
+    pub fn flush(&mut self) {
+        let value = match self.value.get() {
+            Some(value) => value,
+            None => return,
+        };
+        if self.cid.is_some() && self.stored.get() == Some(value) {
+            return;
+        }
+        let cid = match Blockstore.put_cbor(value, Code::Blake2b256) {
+            Ok(cid) => cid,
+            Err(err) => abort!(USR_SERIALIZATION, "failed to store lazy field: {}", err),
+        };
+        self.cid = Some(cid);
+        self.stored = OnceCell::from(value.clone());
+    }
+}
+
+impl<T: DeserializeOwned + Clone> Deref for Lazy<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        self.load()
+    }
+}
+
+impl<T: DeserializeOwned + Clone> DerefMut for Lazy<T> {
+    fn deref_mut(&mut self) -> &mut T {
+        self.load();
+        self.value.get_mut().expect("lazy field was loaded above")
+    }
+}
+
+impl<T: Default> Default for Lazy<T> {
+    fn default() -> Self {
+        Self::new(T::default())
+    }
+}
+
+impl<T: Clone> Clone for Lazy<T> {
+    fn clone(&self) -> Self {
+        Self {
+            cid: self.cid,
+            value: self.value.clone(),
+            stored: self.stored.clone(),
+        }
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for Lazy<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Lazy")
+            .field("cid", &self.cid)
+            .field("value", &self.value.get())
+            .finish()
+    }
+}
+
+/// Serializes the link, failing if the value changed since it was last flushed.
+impl<T: PartialEq> Serialize for Lazy<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self.cid {
+            Some(cid) if self.value.get().is_none() || self.value.get() == self.stored.get() => {
+                cid.serialize(serializer)
+            }
+            _ => Err(serde::ser::Error::custom("lazy field was changed but not flushed")),
+        }
+    }
+}
+
+impl<'de, T> Deserialize<'de> for Lazy<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let cid = Cid::deserialize(deserializer)?;
+        Ok(Self {
+            cid: Some(cid),
+            value: OnceCell::new(),
+            stored: OnceCell::new(),
+        })
+    }
+}
@@ -1,9 +1,27 @@
 extern crate core;
 
 pub mod blockstore;
+pub mod access;
+pub mod batch;
+pub mod env;
+pub mod event;
+pub mod layout;
+pub mod lazy;
+pub mod rbac;
+pub mod ownable;
+pub mod pausable;
+pub mod reentrancy;
+pub mod root;
+pub mod version;
+mod util;
 
-pub use contract::contract;
+pub use contract::{blake2x256, contract, selector_bytes, selector_id};
 pub use blockstore::Blockstore;
+pub use access::{Members, Principal};
+pub use env::Env;
+pub use event::{emit, Event};
+pub use lazy::Lazy;
+pub use root::Rooted;
 pub use fvm_sdk;
 pub use fvm_shared;
 pub use std::convert::TryFrom;
@@ -12,23 +30,44 @@ pub use cid::multihash::Code;
 pub use cid::Cid;
 pub use fvm_ipld_blockstore::Block;
 pub use fvm_sdk as sdk;
-pub use fvm_ipld_encoding::{to_vec, CborStore, DAG_CBOR, RawBytes};
+pub use fvm_ipld_encoding::{from_slice, to_vec, CborStore, DAG_CBOR, RawBytes};
 pub use fvm_ipld_encoding::tuple::{Deserialize_tuple, Serialize_tuple};
 pub use serde_tuple;
 pub use serde;
 pub use fvm_sdk::message::{params_raw, NO_DATA_BLOCK_ID};
 
+/// The items the code generated by the contract macro refers to, as
+/// `::fvm_macro::__private::*`, so that contracts compile whatever they import.
+#[doc(hidden)]
+pub mod __private {
+    pub use crate::access::{Members, Principal};
+    pub use crate::env::Env;
+    pub use crate::event::Event;
+    pub use crate::{abort, access, batch, ownable, pausable, rbac, reentrancy, root, version, State};
+    pub use cid::multihash::Code;
+    pub use cid::Cid;
+    pub use fvm_ipld_encoding::{from_slice, to_vec, RawBytes, DAG_CBOR};
+    pub use fvm_sdk as sdk;
+    pub use fvm_sdk::message::NO_DATA_BLOCK_ID;
+    pub use fvm_shared;
+    pub use serde;
+}
+
 #[macro_export]
 macro_rules! abort {
   ($code:ident, $msg:literal $(, $ex:expr)*) => {
-      fvm_sdk::vm::abort(
-          fvm_shared::error::ExitCode::$code.value(),
-          Some(format!($msg, $($ex,)*).as_str()),
+      $crate::__private::sdk::vm::abort(
+          $crate::__private::fvm_shared::error::ExitCode::$code.value(),
+          ::core::option::Option::Some(::std::format!($msg, $($ex,)*).as_str()),
       )
   };
 }
 
-pub trait State {
-    fn load() -> Self;
-    fn save(&self) -> Cid;
+pub trait State: Sized {
+    /// Loads the state from the current state root, which is kept with it.
+    fn load() -> Rooted<Self>;
+
+    /// Writes the state and makes it the new state root, returning its CID, or returns `None`
+    /// without writing anything if the state is unchanged since it was loaded or last saved.
+    fn save(state: &mut Rooted<Self>) -> Option<Cid>;
 }
\ No newline at end of file
@@ -0,0 +1,91 @@
+//! Single owner access control, enabled with `#[fvm_macro(state, ownable)]`.
+//!
+//! Ownership changes in two steps: the owner proposes a new owner with `transfer_ownership`, who
+//! then takes over with `accept_ownership`.
+
+use fvm_ipld_encoding::tuple::{Deserialize_tuple, Serialize_tuple};
+use fvm_ipld_encoding::RawBytes;
+use fvm_sdk as sdk;
+use fvm_shared::address::Address;
+use fvm_shared::ActorID;
+
+use crate::abort;
+use crate::util::{decode_params, encode_return, resolve_address};
+
+/// The ID of the init actor, which invokes the constructor of actors created through `exec`.
+pub const INIT_ACTOR_ID: ActorID = 1;
+
+/// Sets the owner of a freshly constructed contract to the initial `account`, see
+/// `access::initial_account`, unless the user's constructor set one.
+///
+/// Aborts with `USR_ILLEGAL_ARGUMENT` if the contract would be left without an owner.
+pub fn initialize(owner: &mut Option<ActorID>, account: Option<ActorID>) {
+    if owner.is_some() {
+        return;
+    }
+    match account {
+        Some(account) => *owner = Some(account),
+        None => abort!(
+            USR_ILLEGAL_ARGUMENT,
+            "the contract has no owner, pass the initial account or set it in the constructor"
+        ),
+    }
+}
+
+/// Parameters of the `transfer_ownership` message.
+#[derive(Serialize_tuple, Deserialize_tuple, Clone, Debug, PartialEq, Eq)]
+pub struct TransferOwnershipParams {
+    pub new_owner: Address,
+}
+
+/// Aborts with `USR_FORBIDDEN` unless the caller is the owner.
+pub fn ensure_owner(owner: &Option<ActorID>) {
+    if *owner != Some(sdk::message::caller()) {
+        abort!(USR_FORBIDDEN, "caller is not the owner of the contract");
+    }
+}
+
+/// The handlers of the messages generated for ownable contracts.
+pub mod messages {
+    use super::*;
+
+    /// Returns the ID address of the owner, or `None` if ownership was renounced.
+    pub fn owner(owner: &Option<ActorID>) -> Option<RawBytes> {
+        encode_return(owner.map(Address::new_id))
+    }
+
+    pub fn transfer_ownership(
+        owner: &Option<ActorID>,
+        pending_owner: &mut Option<ActorID>,
+        params: &RawBytes,
+    ) -> Option<RawBytes> {
+        ensure_owner(owner);
+        let params: TransferOwnershipParams = decode_params(params);
+        let new_owner = resolve_address(&params.new_owner);
+        *pending_owner = Some(new_owner);
+        None
+    }
+
+    pub fn accept_ownership(
+        owner: &mut Option<ActorID>,
+        pending_owner: &mut Option<ActorID>,
+    ) -> Option<RawBytes> {
+        let caller = sdk::message::caller();
+        if *pending_owner != Some(caller) {
+            abort!(USR_FORBIDDEN, "caller is not the pending owner of the contract");
+        }
+        *owner = Some(caller);
+        *pending_owner = None;
+        None
+    }
+
+    pub fn renounce_ownership(
+        owner: &mut Option<ActorID>,
+        pending_owner: &mut Option<ActorID>,
+    ) -> Option<RawBytes> {
+        ensure_owner(owner);
+        *owner = None;
+        *pending_owner = None;
+        None
+    }
+}
@@ -0,0 +1,47 @@
+//! Emergency stop, enabled with `#[fvm_macro(state, pausable)]`.
+//!
+//! Messages marked with `#[fvm_macro(message, when_not_paused)]` abort while the contract is
+//! paused. The contract is paused and unpaused by its owner, or by members of [`PAUSER_ROLE`] if
+//! the contract uses the built-in role-based access control.
+
+use fvm_ipld_encoding::RawBytes;
+
+use crate::abort;
+use crate::util::encode_return;
+
+/// The role allowed to pause and unpause contracts using the built-in role-based access control.
+pub const PAUSER_ROLE: &str = "pauser";
+
+/// Aborts with `USR_FORBIDDEN` while the contract is paused.
+pub fn ensure_not_paused(paused: bool) {
+    if paused {
+        abort!(USR_FORBIDDEN, "contract is paused");
+    }
+}
+
+/// The handlers of the messages generated for pausable contracts.
+///
+/// The handlers do not check the caller, the generated messages do so before calling them.
+pub mod messages {
+    use super::*;
+
+    pub fn paused(paused: &bool) -> Option<RawBytes> {
+        encode_return(*paused)
+    }
+
+    pub fn pause(paused: &mut bool) -> Option<RawBytes> {
+        if *paused {
+            abort!(USR_ILLEGAL_STATE, "contract is already paused");
+        }
+        *paused = true;
+        None
+    }
+
+    pub fn unpause(paused: &mut bool) -> Option<RawBytes> {
+        if !*paused {
+            abort!(USR_ILLEGAL_STATE, "contract is not paused");
+        }
+        *paused = false;
+        None
+    }
+}
@@ -0,0 +1,335 @@
+//! Built-in role-based access control, enabled with `#[fvm_macro::contract(rbac)]`.
+//!
+//! Roles are plain strings. Every role is administered by an admin role, which defaults to
+//! [`DEFAULT_ADMIN_ROLE`]; members of a role's admin role may grant and revoke it. Admin roles
+//! are roles themselves, so they form a hierarchy rooted at [`DEFAULT_ADMIN_ROLE`].
+
+use std::cell::{Cell, RefCell};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+
+use cid::Cid;
+use fvm_ipld_encoding::tuple::{Deserialize_tuple, Serialize_tuple};
+use fvm_ipld_encoding::RawBytes;
+use fvm_ipld_hamt::{BytesKey, Hamt};
+use fvm_sdk as sdk;
+use fvm_shared::address::Address;
+use fvm_shared::ActorID;
+use serde::{Deserialize, Serialize};
+
+use crate::event::{emit, Event};
+use crate::util::encode_topic;
+use crate::{abort, Blockstore};
+
+/// The role administering every role without an explicit admin role, including itself.
+pub const DEFAULT_ADMIN_ROLE: &str = "admin";
+
+/// The role assignments of a contract, stored as a HAMT from role name to [`RoleData`].
+///
+/// The HAMT is loaded on first access and kept for the rest of the invocation. Changes are
+/// collected in memory and written by [`Rbac::flush`], which the generated code calls when it
+/// saves the state.
+#[derive(Serialize, Deserialize, Default)]
+#[serde(transparent)]
+pub struct Rbac {
+    root: Cell<Option<Cid>>,
+    #[serde(skip)]
+    hamt: RefCell<Option<Hamt<Blockstore, RoleData>>>,
+    #[serde(skip)]
+    changes: RefCell<BTreeMap<String, RoleData>>,
+}
+
+#[derive(Serialize_tuple, Deserialize_tuple, Clone, Debug, Default, PartialEq, Eq)]
+pub struct RoleData {
+    pub admin_role: Option<String>,
+    pub members: BTreeSet<ActorID>,
+}
+
+impl Rbac {
+    pub fn has_role(&self, role: &str, account: ActorID) -> bool {
+        self.role(role).members.contains(&account)
+    }
+
+    pub fn role_admin(&self, role: &str) -> String {
+        self.role(role)
+            .admin_role
+            .unwrap_or_else(|| DEFAULT_ADMIN_ROLE.to_string())
+    }
+
+    /// Grants `role` to `account` without checking the caller, returns `false` if the account
+    /// already had the role.
+    pub fn grant_role(&mut self, role: &str, account: ActorID) -> bool {
+        let mut data = self.role(role);
+        if !data.members.insert(account) {
+            return false;
+        }
+        self.changes.get_mut().insert(role.to_string(), data);
+        true
+    }
+
+    /// Revokes `role` from `account` without checking the caller, returns `false` if the account
+    /// did not have the role.
+    pub fn revoke_role(&mut self, role: &str, account: ActorID) -> bool {
+        let mut data = self.role(role);
+        if !data.members.remove(&account) {
+            return false;
+        }
+        self.changes.get_mut().insert(role.to_string(), data);
+        true
+    }
+
+    /// Makes `admin_role` the admin role of `role` without checking the caller.
+    pub fn set_role_admin(&mut self, role: &str, admin_role: &str) {
+        let mut data = self.role(role);
+        data.admin_role = Some(admin_role.to_string());
+        self.changes.get_mut().insert(role.to_string(), data);
+    }
+
+    /// Aborts with `USR_FORBIDDEN` unless `account` holds `role`.
+    pub fn ensure_role(&self, role: &str, account: ActorID) {
+        if !self.has_role(role, account) {
+            abort!(USR_FORBIDDEN, "caller is not a member of {}", role);
+        }
+    }
+
+    /// Aborts with `USR_FORBIDDEN` unless `account` holds the admin role of `role`.
+    pub fn ensure_role_admin(&self, role: &str, account: ActorID) {
+        let admin_role = self.role_admin(role);
+        if !self.has_role(&admin_role, account) {
+            abort!(USR_FORBIDDEN, "caller is not a member of {}", admin_role);
+        }
+    }
+
+    /// Writes the changed roles to the HAMT and updates its root. Does nothing if no role
+    /// changed since the last flush.
+    pub fn flush(&self) {
+        let changes = std::mem::take(&mut *self.changes.borrow_mut());
+        if changes.is_empty() {
+            return;
+        }
+        let root = self.root.get();
+        let mut hamt = self.hamt.borrow_mut();
+        let hamt = hamt.get_or_insert_with(|| load(root));
+        for (role, data) in changes {
+            if let Err(err) = hamt.set(BytesKey(role.into_bytes()), data) {
+                abort!(USR_ILLEGAL_STATE, "failed to set role: {}", err);
+            }
+        }
+        match hamt.flush() {
+            Ok(root) => self.root.set(Some(root)),
+            Err(err) => abort!(USR_ILLEGAL_STATE, "failed to flush roles: {}", err),
+        }
+    }
+
+    fn role(&self, role: &str) -> RoleData {
+        if let Some(data) = self.changes.borrow().get(role) {
+            return data.clone();
+        }
+        let root = self.root.get();
+        let mut hamt = self.hamt.borrow_mut();
+        let hamt = hamt.get_or_insert_with(|| load(root));
+        match hamt.get(&BytesKey(role.as_bytes().to_vec())) {
+            Ok(data) => data.cloned().unwrap_or_default(),
+            Err(err) => abort!(USR_ILLEGAL_STATE, "failed to get role {}: {}", role, err),
+        }
+    }
+}
+
+/// Grants `role` to the initial account of a freshly constructed contract, unless the
+/// constructor granted it to someone already.
+///
+/// Aborts with `USR_ILLEGAL_ARGUMENT` if neither happened, see
+/// [`initial_account`](crate::access::initial_account).
+pub fn initialize(rbac: &mut Rbac, role: &str, account: Option<ActorID>) {
+    if !rbac.role(role).members.is_empty() {
+        return;
+    }
+    match account {
+        Some(account) => {
+            rbac.grant_role(role, account);
+            emit(&RoleGranted {
+                role: role.to_string(),
+                account,
+                sender: sdk::message::caller(),
+            });
+        }
+        None => abort!(
+            USR_ILLEGAL_ARGUMENT,
+            "the {} role has no member, pass the initial account or grant it in the constructor",
+            role
+        ),
+    }
+}
+
+fn load(root: Option<Cid>) -> Hamt<Blockstore, RoleData> {
+    match root {
+        None => Hamt::new(Blockstore),
+        Some(root) => match Hamt::load(&root, Blockstore) {
+            Ok(hamt) => hamt,
+            Err(err) => abort!(USR_ILLEGAL_STATE, "failed to load roles: {}", err),
+        },
+    }
+}
+
+/// Clones the root and the unflushed changes, the clone loads the HAMT again when accessed.
+impl Clone for Rbac {
+    fn clone(&self) -> Self {
+        Self {
+            root: self.root.clone(),
+            hamt: RefCell::new(None),
+            changes: self.changes.clone(),
+        }
+    }
+}
+
+impl PartialEq for Rbac {
+    fn eq(&self, other: &Self) -> bool {
+        self.root == other.root && self.changes == other.changes
+    }
+}
+
+impl Eq for Rbac {}
+
+impl fmt::Debug for Rbac {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Rbac")
+            .field("root", &self.root.get())
+            .field("changes", &self.changes.borrow())
+            .finish()
+    }
+}
+
+/// Parameters of the `grant_role`, `revoke_role`, `renounce_role` and `has_role` messages.
+#[derive(Serialize_tuple, Deserialize_tuple, Clone, Debug, PartialEq, Eq)]
+pub struct RoleParams {
+    pub role: String,
+    pub account: Address,
+}
+
+/// Parameters of the `set_role_admin` message.
+#[derive(Serialize_tuple, Deserialize_tuple, Clone, Debug, PartialEq, Eq)]
+pub struct RoleAdminParams {
+    pub role: String,
+    pub admin_role: String,
+}
+
+/// Emitted when `account` is granted `role` by `sender`.
+#[derive(Serialize_tuple, Deserialize_tuple, Clone, Debug, PartialEq, Eq)]
+pub struct RoleGranted {
+    pub role: String,
+    pub account: ActorID,
+    pub sender: ActorID,
+}
+
+impl Event for RoleGranted {
+    const NAME: &'static str = "RoleGranted";
+
+    fn topics(&self) -> Vec<RawBytes> {
+        vec![encode_topic(&self.role), encode_topic(&self.account), encode_topic(&self.sender)]
+    }
+}
+
+/// Emitted when `role` is revoked from `account` by `sender`, who is the account itself if it
+/// renounced the role.
+#[derive(Serialize_tuple, Deserialize_tuple, Clone, Debug, PartialEq, Eq)]
+pub struct RoleRevoked {
+    pub role: String,
+    pub account: ActorID,
+    pub sender: ActorID,
+}
+
+impl Event for RoleRevoked {
+    const NAME: &'static str = "RoleRevoked";
+
+    fn topics(&self) -> Vec<RawBytes> {
+        vec![encode_topic(&self.role), encode_topic(&self.account), encode_topic(&self.sender)]
+    }
+}
+
+/// Emitted when the admin role of `role` changes from `previous_admin_role` to
+/// `new_admin_role`.
+#[derive(Serialize_tuple, Deserialize_tuple, Clone, Debug, PartialEq, Eq)]
+pub struct RoleAdminChanged {
+    pub role: String,
+    pub previous_admin_role: String,
+    pub new_admin_role: String,
+}
+
+impl Event for RoleAdminChanged {
+    const NAME: &'static str = "RoleAdminChanged";
+
+    fn topics(&self) -> Vec<RawBytes> {
+        vec![
+            encode_topic(&self.role),
+            encode_topic(&self.previous_admin_role),
+            encode_topic(&self.new_admin_role),
+        ]
+    }
+}
+
+/// The handlers of the messages generated for contracts with role-based access control.
+///
+/// Every change of a role emits a [`RoleGranted`], [`RoleRevoked`] or [`RoleAdminChanged`]
+/// event. Granting a role an account already has, or revoking one it does not have, changes
+/// nothing and emits nothing.
+pub mod messages {
+    use super::*;
+    use crate::util::{decode_params, encode_return, resolve_address};
+
+    pub fn grant_role(rbac: &mut Rbac, params: &RawBytes) -> Option<RawBytes> {
+        let params: RoleParams = decode_params(params);
+        let sender = sdk::message::caller();
+        rbac.ensure_role_admin(&params.role, sender);
+        let account = resolve_address(&params.account);
+        if rbac.grant_role(&params.role, account) {
+            emit(&RoleGranted { role: params.role, account, sender });
+        }
+        None
+    }
+
+    pub fn revoke_role(rbac: &mut Rbac, params: &RawBytes) -> Option<RawBytes> {
+        let params: RoleParams = decode_params(params);
+        let sender = sdk::message::caller();
+        rbac.ensure_role_admin(&params.role, sender);
+        let account = resolve_address(&params.account);
+        if rbac.revoke_role(&params.role, account) {
+            emit(&RoleRevoked { role: params.role, account, sender });
+        }
+        None
+    }
+
+    /// Revokes a role from the caller, who must pass its own address as the account.
+    pub fn renounce_role(rbac: &mut Rbac, params: &RawBytes) -> Option<RawBytes> {
+        let params: RoleParams = decode_params(params);
+        let account = resolve_address(&params.account);
+        if account != sdk::message::caller() {
+            abort!(USR_FORBIDDEN, "roles can only be renounced for the caller itself");
+        }
+        if rbac.revoke_role(&params.role, account) {
+            emit(&RoleRevoked { role: params.role, account, sender: account });
+        }
+        None
+    }
+
+    pub fn set_role_admin(rbac: &mut Rbac, params: &RawBytes) -> Option<RawBytes> {
+        let params: RoleAdminParams = decode_params(params);
+        rbac.ensure_role_admin(&params.role, sdk::message::caller());
+        let previous_admin_role = rbac.role_admin(&params.role);
+        rbac.set_role_admin(&params.role, &params.admin_role);
+        emit(&RoleAdminChanged {
+            role: params.role,
+            previous_admin_role,
+            new_admin_role: params.admin_role,
+        });
+        None
+    }
+
+    pub fn has_role(rbac: &Rbac, params: &RawBytes) -> Option<RawBytes> {
+        let params: RoleParams = decode_params(params);
+        let has_role = match sdk::actor::resolve_address(&params.account) {
+            Some(account) => rbac.has_role(&params.role, account),
+            None => false,
+        };
+        encode_return(has_role)
+    }
+}
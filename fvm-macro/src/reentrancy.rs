@@ -0,0 +1,26 @@
+//! Reentrancy guard of the messages marked with `#[fvm_macro(message, nonreentrant)]`.
+//!
+//! The lock is a flag in the persisted state, so it is visible to nested invocations of the actor
+//! made through outgoing sends. An abort reverts the state, which releases the lock as well.
+
+use crate::abort;
+
+/// Aborts with `USR_FORBIDDEN` if the lock is held.
+///
+/// Read-only messages only check the lock, since they never save the state.
+pub fn ensure_unlocked(locked: bool) {
+    if locked {
+        abort!(USR_FORBIDDEN, "reentrant call");
+    }
+}
+
+/// Takes the lock, aborting with `USR_FORBIDDEN` if it is already held.
+pub fn enter(locked: &mut bool) {
+    ensure_unlocked(*locked);
+    *locked = true;
+}
+
+/// Releases the lock.
+pub fn exit(locked: &mut bool) {
+    *locked = false;
+}
@@ -0,0 +1,121 @@
+//! The state root a state was loaded from, kept next to the state so that saving an unchanged
+//! state is a no-op.
+//!
+//! The generated `State::load` wraps the state in a [`Rooted`] together with its root and block,
+//! and `State::save` only writes a new root if the serialized state differs from that block. A
+//! state migrated on load keeps its root but not the block, so that it is written back.
+
+use std::ops::{Deref, DerefMut};
+
+use cid::Cid;
+
+/// A state together with the root it was loaded from or last saved to.
+///
+/// Dereferences to the state, so messages and fields are reached as on the state itself.
+#[derive(Clone, Debug)]
+pub struct Rooted<S> {
+    state: S,
+    root: Option<Cid>,
+    /// The block of `root`, `None` if it does not hold the state.
+    block: Option<Vec<u8>>,
+}
+
+impl<S> Rooted<S> {
+    /// Wraps a state without a root, such as a freshly constructed one, which the next save
+    /// always writes.
+    pub fn new(state: S) -> Self {
+        Self {
+            state,
+            root: None,
+            block: None,
+        }
+    }
+
+    /// Wraps a state decoded from `block`, the block of `root`.
+    pub fn loaded(state: S, root: Cid, block: Vec<u8>) -> Self {
+        Self {
+            state,
+            root: Some(root),
+            block: Some(block),
+        }
+    }
+
+    /// Wraps a state migrated from an older version of the state stored under `root`, which
+    /// the next save always writes.
+    pub fn migrated(state: S, root: Cid) -> Self {
+        Self {
+            state,
+            root: Some(root),
+            block: None,
+        }
+    }
+
+    /// Returns the root the state was loaded from or last saved to, `None` if it has none yet.
+    pub fn root(&self) -> Option<Cid> {
+        self.root
+    }
+
+    /// Returns `true` if the root does not hold the state, i.e. the state is new or was migrated
+    /// on load, and must be saved even if no message changed it.
+    pub fn is_stale(&self) -> bool {
+        self.block.is_none()
+    }
+
+    /// Returns `true` if `block` is the block of the root of the state.
+    pub fn is_unchanged(&self, block: &[u8]) -> bool {
+        self.block.as_deref() == Some(block)
+    }
+
+    /// Records `root` and its block as the root of the state, once the state was saved.
+    pub fn set_root(&mut self, root: Cid, block: Vec<u8>) {
+        self.root = Some(root);
+        self.block = Some(block);
+    }
+
+    /// Returns the state, dropping its root.
+    pub fn into_inner(self) -> S {
+        self.state
+    }
+}
+
+impl<S> Deref for Rooted<S> {
+    type Target = S;
+
+    fn deref(&self) -> &S {
+        &self.state
+    }
+}
+
+impl<S> DerefMut for Rooted<S> {
+    fn deref_mut(&mut self) -> &mut S {
+        &mut self.state
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cid::multihash::{Code, MultihashDigest};
+
+    #[test]
+    fn only_the_block_of_the_root_is_unchanged() {
+        let mut state = Rooted::new(1u64);
+        assert_eq!(state.root(), None);
+        assert!(state.is_stale());
+        assert!(!state.is_unchanged(&[]));
+
+        let root = Cid::new_v1(0x71, Code::Blake2b256.digest(&[0x01]));
+        *state += 1;
+        state.set_root(root, vec![0x02]);
+        assert_eq!(state.root(), Some(root));
+        assert!(!state.is_stale());
+        assert!(state.is_unchanged(&[0x02]));
+        assert!(!state.is_unchanged(&[0x01]));
+        assert_eq!(state.into_inner(), 2);
+
+        let state = Rooted::migrated(3u64, root);
+        assert_eq!(state.root(), Some(root));
+        assert!(state.is_stale());
+        assert!(!state.is_unchanged(&[0x02]));
+    }
+}
@@ -0,0 +1,40 @@
+use fvm_ipld_encoding::RawBytes;
+use fvm_sdk as sdk;
+use fvm_shared::address::Address;
+use fvm_shared::ActorID;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use crate::abort;
+
+/// Decodes the CBOR params of a message, aborting with `USR_SERIALIZATION` on failure.
+pub(crate) fn decode_params<T: DeserializeOwned>(params: &RawBytes) -> T {
+    match params.deserialize() {
+        Ok(params) => params,
+        Err(err) => abort!(USR_SERIALIZATION, "failed to deserialize params: {:?}", err),
+    }
+}
+
+/// Encodes the return value of a message, aborting with `USR_SERIALIZATION` on failure.
+pub(crate) fn encode_return<T: Serialize>(ret: T) -> Option<RawBytes> {
+    match RawBytes::serialize(ret) {
+        Ok(ret) => Some(ret),
+        Err(err) => abort!(USR_SERIALIZATION, "failed to serialize return value: {:?}", err),
+    }
+}
+
+/// Encodes an event topic, aborting with `USR_SERIALIZATION` on failure.
+pub(crate) fn encode_topic<T: Serialize>(topic: &T) -> RawBytes {
+    match RawBytes::serialize(topic) {
+        Ok(topic) => topic,
+        Err(err) => abort!(USR_SERIALIZATION, "failed to serialize event topic: {:?}", err),
+    }
+}
+
+/// Resolves an address to its actor ID, aborting with `USR_NOT_FOUND` if there is none.
+pub(crate) fn resolve_address(address: &Address) -> ActorID {
+    match sdk::actor::resolve_address(address) {
+        Some(id) => id,
+        None => abort!(USR_NOT_FOUND, "failed to resolve address {}", address),
+    }
+}
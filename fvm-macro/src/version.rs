@@ -0,0 +1,82 @@
+//! Versioned state roots, enabled with `#[fvm_macro(state, version = N)]`.
+//!
+//! A versioned state is stored in a [`Versioned`] envelope, the CBOR map
+//! `{"fvm_macro::version": version, "fvm_macro::state": state}`. Roots written at an older
+//! version are upgraded on load by the `#[fvm_macro(migrate(from = N))]` functions of the state.
+
+use fvm_ipld_encoding::from_slice;
+use serde::de::{DeserializeOwned, IgnoredAny};
+use serde::{Deserialize, Serialize};
+
+use crate::abort;
+
+/// The envelope a versioned state root is written in.
+///
+/// Its keys are no valid field names, so the root of a state written before it was versioned is
+/// never mistaken for an envelope, whatever its encoding.
+#[derive(Serialize)]
+pub struct Versioned<'a, T> {
+    #[serde(rename = "fvm_macro::version")]
+    pub version: u64,
+    #[serde(rename = "fvm_macro::state")]
+    pub state: &'a T,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Envelope<T> {
+    #[serde(rename = "fvm_macro::version")]
+    version: u64,
+    #[serde(rename = "fvm_macro::state")]
+    state: T,
+}
+
+/// Returns the version of a versioned state root, or `None` if the root is not versioned.
+pub fn of(block: &[u8]) -> Option<u64> {
+    from_slice::<Envelope<IgnoredAny>>(block)
+        .ok()
+        .map(|envelope| envelope.version)
+}
+
+/// Decodes the state of a versioned state root, aborting with `USR_SERIALIZATION` on failure.
+pub fn decode<T: DeserializeOwned>(block: &[u8]) -> T {
+    match from_slice::<Envelope<T>>(block) {
+        Ok(envelope) => envelope.state,
+        Err(err) => abort!(USR_SERIALIZATION, "failed to deserialize state: {}", err),
+    }
+}
+
+/// Decodes a state root written before the state was versioned, aborting with
+/// `USR_SERIALIZATION` on failure.
+pub fn decode_unversioned<T: DeserializeOwned>(block: &[u8]) -> T {
+    match from_slice::<T>(block) {
+        Ok(state) => state,
+        Err(err) => abort!(USR_SERIALIZATION, "failed to deserialize state: {}", err),
+    }
+}
+
+/// Aborts with `USR_ILLEGAL_STATE` for a state root the contract has no migration for.
+pub fn unsupported(version: Option<u64>) -> ! {
+    match version {
+        Some(version) => abort!(USR_ILLEGAL_STATE, "unsupported state version {}", version),
+        None => abort!(USR_ILLEGAL_STATE, "unsupported unversioned state"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fvm_ipld_encoding::to_vec;
+
+    #[test]
+    fn only_envelopes_are_versioned() {
+        let envelope = to_vec(&Versioned { version: 2, state: &(7u64, "x") }).unwrap();
+        assert_eq!(of(&envelope), Some(2));
+
+        // An unversioned root with the shape of the old `[version, state]` encoding.
+        assert_eq!(of(&to_vec(&(2u64, "x")).unwrap()), None);
+        let mut map = std::collections::BTreeMap::new();
+        map.insert("version", 2u64);
+        assert_eq!(of(&to_vec(&map).unwrap()), None);
+    }
+}
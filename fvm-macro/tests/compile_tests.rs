@@ -0,0 +1,10 @@
+//! Expands and builds the contracts under `tests/ui`. The passing contracts run their `main`,
+//! which checks the generated code natively, i.e. without calling any syscall, or against the
+//! few syscalls it stubs itself.
+
+#[test]
+fn contract() {
+    let t = trybuild::TestCases::new();
+    t.pass("tests/ui/pass/*.rs");
+    t.compile_fail("tests/ui/fail/*.rs");
+}
@@ -0,0 +1,21 @@
+#[fvm_macro::contract(rbac)]
+pub mod clash {
+    use fvm_macro::*;
+
+    #[fvm_macro(state, ownable)]
+    pub struct Clash {
+        pub owner: u64,
+        pub rbac: u64,
+        pub count: u64,
+    }
+
+    impl Clash {
+        #[fvm_macro(message)]
+        pub fn bump(&mut self) -> Option<RawBytes> {
+            self.count += 1;
+            None
+        }
+    }
+}
+
+fn main() {}
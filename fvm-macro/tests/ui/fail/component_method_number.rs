@@ -0,0 +1,20 @@
+#[fvm_macro::contract]
+pub mod vault {
+    use fvm_macro::*;
+
+    #[fvm_macro(state, ownable)]
+    pub struct Vault {
+        pub balance: u64,
+    }
+
+    #[fvm_macro(impl, namespace = "Admin")]
+    impl Vault {
+        // The method number of `Ownable::owner`.
+        #[fvm_macro(message, selector = 0x4FA43C8C)]
+        pub fn owner(&self) -> Option<RawBytes> {
+            None
+        }
+    }
+}
+
+fn main() {}
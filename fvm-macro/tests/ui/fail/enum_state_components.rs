@@ -0,0 +1,39 @@
+#[fvm_macro::contract(rbac)]
+pub mod auction {
+    use fvm_macro::*;
+
+    #[fvm_macro(state)]
+    #[derive(Default)]
+    pub enum Auction {
+        #[default]
+        Pending,
+        Closed(u64),
+    }
+}
+
+#[fvm_macro::contract]
+pub mod pair {
+    use fvm_macro::*;
+
+    #[fvm_macro(state)]
+    pub struct Pair(pub u64, pub u64);
+
+    impl Pair {
+        #[fvm_macro(message, nonreentrant)]
+        pub fn swap(&mut self) -> Option<RawBytes> {
+            *self = Pair(self.1, self.0);
+            None
+        }
+    }
+}
+
+#[fvm_macro::contract]
+pub mod stage {
+    #[fvm_macro(state)]
+    pub enum Stage {
+        Pending,
+        Closed,
+    }
+}
+
+fn main() {}
@@ -0,0 +1,18 @@
+#[fvm_macro::contract]
+pub mod proxy {
+    use fvm_macro::*;
+
+    #[fvm_macro(state)]
+    pub struct Proxy {
+        pub count: u64,
+    }
+
+    impl Proxy {
+        #[fvm_macro(message, selector = _)]
+        pub fn fallback(&self, params: RawBytes) -> Option<RawBytes> {
+            None
+        }
+    }
+}
+
+fn main() {}
@@ -0,0 +1,23 @@
+#[fvm_macro::contract]
+pub mod proxy {
+    use fvm_macro::*;
+
+    #[fvm_macro(state)]
+    pub struct Proxy {
+        pub count: u64,
+    }
+
+    impl Proxy {
+        #[fvm_macro(message, selector = _)]
+        pub fn first(&mut self, method: u64, params: RawBytes) -> Option<RawBytes> {
+            None
+        }
+
+        #[fvm_macro(message, selector = _)]
+        pub fn second(&self, method: u64, params: RawBytes) -> Option<RawBytes> {
+            None
+        }
+    }
+}
+
+fn main() {}
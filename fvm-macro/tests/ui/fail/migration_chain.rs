@@ -0,0 +1,28 @@
+#[fvm_macro::contract]
+pub mod skipped {
+    use fvm_macro::*;
+
+    #[fvm_macro(state, version = 2)]
+    pub struct Counter {
+        pub count: u64,
+    }
+
+    impl Counter {
+        #[fvm_macro(migrate(from = 1))]
+        fn from_v1(old: u64) -> Self {
+            Counter { count: old }
+        }
+    }
+}
+
+#[fvm_macro::contract]
+pub mod unmigrated {
+    use fvm_macro::*;
+
+    #[fvm_macro(state, version = 1)]
+    pub struct Counter {
+        pub count: u64,
+    }
+}
+
+fn main() {}
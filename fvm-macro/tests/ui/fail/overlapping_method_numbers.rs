@@ -0,0 +1,27 @@
+#[fvm_macro::contract]
+pub mod token {
+    use fvm_macro::*;
+
+    #[fvm_macro(state)]
+    pub struct Token {
+        pub supply: u64,
+    }
+
+    impl Token {
+        #[fvm_macro(message)]
+        pub fn mint(&mut self) -> Option<RawBytes> {
+            None
+        }
+    }
+
+    #[fvm_macro(impl, namespace = "Admin")]
+    impl Token {
+        // The method number of `mint`.
+        #[fvm_macro(message, selector = 0x00000002)]
+        pub fn halt(&mut self) -> Option<RawBytes> {
+            None
+        }
+    }
+}
+
+fn main() {}
@@ -0,0 +1,29 @@
+#[fvm_macro::contract]
+pub mod token {
+    use fvm_macro::*;
+
+    #[fvm_macro(state, pausable)]
+    pub struct Token {
+        pub supply: u64,
+    }
+}
+
+#[fvm_macro::contract]
+pub mod counter {
+    use fvm_macro::*;
+
+    #[fvm_macro(state)]
+    pub struct Counter {
+        pub count: u64,
+    }
+
+    impl Counter {
+        #[fvm_macro(message, when_not_paused)]
+        pub fn increment(&mut self) -> Option<RawBytes> {
+            self.count += 1;
+            None
+        }
+    }
+}
+
+fn main() {}
@@ -0,0 +1,21 @@
+#[fvm_macro::contract]
+pub mod hidden {
+    use fvm_macro::*;
+
+    // The state keeps its private visibility.
+    #[fvm_macro(state)]
+    struct Hidden {
+        pub count: u64,
+    }
+
+    impl Hidden {
+        #[fvm_macro(message)]
+        pub fn count(&self) -> Option<RawBytes> {
+            None
+        }
+    }
+}
+
+fn main() {
+    let _ = hidden::Hidden::default();
+}
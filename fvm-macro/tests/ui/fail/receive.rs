@@ -0,0 +1,18 @@
+#[fvm_macro::contract]
+pub mod wallet {
+    use fvm_macro::*;
+
+    #[fvm_macro(state)]
+    pub struct Wallet {
+        pub deposits: u64,
+    }
+
+    impl Wallet {
+        #[fvm_macro(receive)]
+        pub fn receive(&mut self) {
+            self.deposits += 1;
+        }
+    }
+}
+
+fn main() {}
@@ -0,0 +1,5 @@
+const OWNER: u32 = fvm_macro::selector_id!(42);
+
+fn main() {
+    let _ = OWNER;
+}
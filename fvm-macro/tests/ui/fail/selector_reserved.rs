@@ -0,0 +1,19 @@
+#[fvm_macro::contract]
+pub mod token {
+    use fvm_macro::*;
+
+    #[fvm_macro(state)]
+    pub struct Token {
+        pub supply: u64,
+    }
+
+    impl Token {
+        // Method number 1 belongs to the constructor.
+        #[fvm_macro(message, selector = 0x00000001)]
+        pub fn supply(&self) -> Option<RawBytes> {
+            None
+        }
+    }
+}
+
+fn main() {}
@@ -0,0 +1,18 @@
+#[fvm_macro::contract]
+pub mod counter {
+    use fvm_macro::*;
+
+    #[fvm_macro(state, version = 0)]
+    pub struct Counter {
+        pub count: u64,
+    }
+
+    impl Counter {
+        #[fvm_macro(message)]
+        pub fn count(&self) -> Option<RawBytes> {
+            None
+        }
+    }
+}
+
+fn main() {}
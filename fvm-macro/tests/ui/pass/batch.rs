@@ -0,0 +1,47 @@
+#[fvm_macro::contract(batch)]
+pub mod counter {
+    use fvm_macro::*;
+
+    #[fvm_macro(state)]
+    pub struct Counter {
+        pub count: u64,
+    }
+
+    impl Counter {
+        #[fvm_macro(message)]
+        pub fn increment(&mut self) -> Option<RawBytes> {
+            self.count += 1;
+            None
+        }
+
+        #[fvm_macro(message)]
+        pub fn count(&self) -> Option<RawBytes> {
+            None
+        }
+    }
+}
+
+use counter::Counter;
+use fvm_macro::{from_slice, selector_id, to_vec, RawBytes};
+
+fn main() {
+    // The batch message is dispatched under the hash of `Batch::batch` and always saves.
+    let batch = selector_id!("Batch::batch") as u64;
+    let abi: serde_json::Value = serde_json::from_str(counter::ABI).unwrap();
+    let message = abi["abi"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|message| message["method"] == "batch")
+        .expect("missing batch");
+    assert_eq!(message["methodNumber"], batch.to_string());
+    assert_eq!(message["inputs"][0]["name"], "calls");
+    assert!(Counter::__mutates(batch));
+
+    // The params are a CBOR array of `(method_num, params)` pairs.
+    let calls = vec![(2u64, RawBytes::default()), (3u64, RawBytes::new(vec![0x01]))];
+    let params = RawBytes::serialize(&calls).unwrap();
+    let decoded: Vec<(u64, RawBytes)> = from_slice(params.bytes()).unwrap();
+    assert_eq!(decoded, calls);
+    assert_eq!(to_vec(&decoded).unwrap(), params.bytes());
+}
@@ -0,0 +1,83 @@
+mod hex {
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(value: &u64, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&format!("{:x}", value))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<u64, D::Error> {
+        let hex = String::deserialize(deserializer)?;
+        u64::from_str_radix(&hex, 16).map_err(serde::de::Error::custom)
+    }
+}
+
+#[fvm_macro::contract]
+pub mod registry {
+    use fvm_macro::*;
+
+    // `Debug` and `Clone` are derived by the user and merged with the generated derives.
+    #[fvm_macro(state)]
+    #[derive(Debug, Clone, PartialEq)]
+    pub(crate) struct Registry {
+        #[serde(with = "crate::hex")]
+        pub key: u64,
+        pub name: String,
+    }
+
+    impl Registry {
+        #[fvm_macro(message)]
+        pub fn rename(&mut self) -> Option<RawBytes> {
+            self.name.push('!');
+            None
+        }
+    }
+}
+
+#[fvm_macro::contract]
+pub mod handles {
+    use fvm_macro::*;
+
+    pub struct Handle(pub u64);
+
+    // The field implements none of `Clone`, `Debug` or `Default`.
+    #[fvm_macro(state, no_derive)]
+    pub struct Handles {
+        #[fvm_macro(transient)]
+        pub handle: Option<Handle>,
+        pub count: u64,
+    }
+
+    impl Handles {
+        #[fvm_macro(constructor)]
+        pub fn new() -> Self {
+            Self {
+                handle: Some(Handle(1)),
+                count: 0,
+            }
+        }
+
+        #[fvm_macro(message)]
+        pub fn count(&self) -> Option<RawBytes> {
+            None
+        }
+    }
+}
+
+use handles::Handles;
+use registry::Registry;
+
+fn main() {
+    let registry = Registry {
+        key: 255,
+        name: "a".into(),
+    };
+    let encoded = serde_json::to_value(&registry).unwrap();
+    assert_eq!(encoded, serde_json::json!(["ff", "a"]));
+    let decoded: Registry = serde_json::from_value(encoded).unwrap();
+    assert_eq!(decoded, registry.clone());
+    assert_eq!(Registry::default().key, 0);
+
+    let handles = Handles::new();
+    assert_eq!(handles.handle.as_ref().map(|handle| handle.0), Some(1));
+    assert_eq!(serde_json::to_value(&handles).unwrap(), serde_json::json!([0]));
+}
@@ -0,0 +1,66 @@
+#[fvm_macro::contract]
+pub mod auction {
+    use fvm_macro::*;
+
+    #[fvm_macro(state)]
+    #[derive(Default, PartialEq)]
+    pub enum Auction {
+        #[default]
+        Pending,
+        Open { highest_bid: u64 },
+        Closed(u64),
+    }
+
+    impl Auction {
+        #[fvm_macro(message)]
+        pub fn open(&mut self) -> Option<RawBytes> {
+            *self = Auction::Open { highest_bid: 0 };
+            None
+        }
+
+        #[fvm_macro(message, selector = _)]
+        pub fn close(&mut self, _method: u64, _params: RawBytes) -> Option<RawBytes> {
+            if let Auction::Open { highest_bid } = *self {
+                *self = Auction::Closed(highest_bid);
+            }
+            None
+        }
+    }
+}
+
+#[fvm_macro::contract]
+pub mod pair {
+    #[fvm_macro(state)]
+    pub struct Pair(pub u64, pub String);
+}
+
+use auction::Auction;
+use fvm_macro::layout::{Encoding, StorageLayout};
+use fvm_macro::{from_slice, to_vec, RawBytes, Rooted};
+use pair::Pair;
+
+fn main() {
+    // The messages of an enum state switch between its variants.
+    let mut state = Rooted::new(Auction::default());
+    assert_eq!(*state, Auction::Pending);
+    assert_eq!(Auction::__dispatch(&mut state, 2, RawBytes::default()), None);
+    assert_eq!(*state, Auction::Open { highest_bid: 0 });
+    assert_eq!(Auction::__dispatch(&mut state, 100, RawBytes::default()), None);
+    assert_eq!(*state, Auction::Closed(0));
+
+    // An enum is stored tagged with its variant name, and its layout lists the variants.
+    let decoded: Auction = from_slice(&to_vec(&Auction::Closed(7)).unwrap()).unwrap();
+    assert_eq!(decoded, Auction::Closed(7));
+    let layout = StorageLayout::from_json(auction::STORAGE_LAYOUT).unwrap();
+    assert_eq!(layout.encoding, Encoding::Enum);
+    let variants = layout.fields.iter().map(|field| field.name.as_str()).collect::<Vec<_>>();
+    assert_eq!(variants, ["Pending", "Open", "Closed"]);
+
+    // A tuple struct is stored as an array of its fields.
+    let block = to_vec(&Pair(1, "one".to_string())).unwrap();
+    assert_eq!(block, to_vec(&(1u64, "one")).unwrap());
+    let decoded: Pair = from_slice(&block).unwrap();
+    assert_eq!((decoded.0, decoded.1.as_str()), (1, "one"));
+    let layout = StorageLayout::from_json(pair::STORAGE_LAYOUT).unwrap();
+    assert_eq!(layout.encoding, Encoding::Tuple);
+}
@@ -0,0 +1,44 @@
+#[fvm_macro::contract]
+pub mod tracker {
+    use fvm_macro::*;
+
+    #[fvm_macro(state)]
+    pub struct Tracker {
+        pub last_caller: u64,
+        pub last_epoch: i64,
+    }
+
+    impl Tracker {
+        #[fvm_macro(message)]
+        pub fn bump(&mut self, env: &Env) -> Option<RawBytes> {
+            self.last_caller = env.caller;
+            self.last_epoch = env.epoch;
+            None
+        }
+    }
+}
+
+use fvm_macro::Env;
+use tracker::Tracker;
+
+fn main() {
+    // The context is not part of the message params.
+    let abi: serde_json::Value = serde_json::from_str(tracker::ABI).unwrap();
+    let bump = &abi["abi"][0];
+    assert_eq!(bump["method"], "bump");
+    assert_eq!(bump["inputs"], serde_json::json!([]));
+
+    // Messages taking the context run natively against a hand-built one.
+    let env = Env {
+        caller: 101,
+        receiver: 1000,
+        value: Default::default(),
+        epoch: 42,
+        method_number: 2,
+        balance: Default::default(),
+    };
+    let mut state = Tracker::default();
+    assert_eq!(state.bump(&env), None);
+    assert_eq!(state.last_caller, 101);
+    assert_eq!(state.last_epoch, 42);
+}
@@ -0,0 +1,47 @@
+#[fvm_macro::contract]
+pub mod proxy {
+    use fvm_macro::*;
+
+    #[fvm_macro(state)]
+    pub struct Proxy {
+        pub count: u64,
+        pub last_method: u64,
+    }
+
+    impl Proxy {
+        #[fvm_macro(message)]
+        pub fn increment(&mut self) -> Option<RawBytes> {
+            self.count += 1;
+            None
+        }
+
+        #[fvm_macro(message, selector = _)]
+        pub fn fallback(&mut self, method: u64, params: RawBytes) -> Option<RawBytes> {
+            self.last_method = method;
+            Some(params)
+        }
+    }
+}
+
+use fvm_macro::{RawBytes, Rooted};
+use proxy::Proxy;
+
+fn main() {
+    // The fallback takes no method number of its own.
+    let abi: serde_json::Value = serde_json::from_str(proxy::ABI).unwrap();
+    let increment = &abi["abi"][0];
+    assert_eq!(increment["method"], "increment");
+    let increment: u64 = increment["methodNumber"].as_str().unwrap().parse().unwrap();
+
+    let mut state = Rooted::new(Proxy::default());
+    assert_eq!(Proxy::__dispatch(&mut state, increment, RawBytes::default()), None);
+    assert_eq!(state.count, 1);
+    assert_eq!(state.last_method, 0);
+
+    // Any other method number reaches the fallback with the raw params.
+    let params = RawBytes::new(vec![0x82, 0x01, 0x02]);
+    assert_eq!(Proxy::__dispatch(&mut state, 4242, params.clone()), Some(params));
+    assert_eq!(state.count, 1);
+    assert_eq!(state.last_method, 4242);
+    assert!(Proxy::__mutates(4242));
+}
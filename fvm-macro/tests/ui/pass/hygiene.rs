@@ -0,0 +1,64 @@
+// The contract module neither imports `fvm_macro::*` nor avoids the names the generated code
+// relies on.
+#[fvm_macro::contract]
+pub mod counter {
+    pub trait State {}
+
+    pub struct Blockstore;
+
+    pub mod sdk {}
+
+    pub type RawBytes = Vec<u8>;
+
+    pub type Result<T> = core::result::Result<T, String>;
+
+    pub const NO_DATA_BLOCK_ID: u32 = 7;
+
+    #[allow(unused_macros)]
+    macro_rules! abort {
+        () => {};
+    }
+
+    #[fvm_macro(state)]
+    pub struct Counter {
+        pub count: u64,
+    }
+
+    impl State for Counter {}
+
+    impl Counter {
+        #[fvm_macro(constructor)]
+        pub fn new(params: fvm_macro::RawBytes) -> Self {
+            Self { count: params.len() as u64 }
+        }
+
+        #[fvm_macro(message)]
+        pub fn add(&mut self, params: fvm_macro::RawBytes) -> Option<fvm_macro::RawBytes> {
+            self.count += params.len() as u64;
+            None
+        }
+
+        #[fvm_macro(message)]
+        pub fn count(&self) -> Option<fvm_macro::RawBytes> {
+            None
+        }
+    }
+
+    pub fn local() -> (RawBytes, Result<u32>) {
+        (vec![], Ok(NO_DATA_BLOCK_ID))
+    }
+}
+
+use counter::Counter;
+
+fn main() {
+    let abi: serde_json::Value = serde_json::from_str(counter::ABI).unwrap();
+    assert_eq!(abi["abi"][0]["method"], "add");
+    assert!(Counter::__mutates(2));
+    assert!(!Counter::__mutates(3));
+
+    let mut state = Counter::new(fvm_macro::RawBytes::new(vec![0]));
+    assert_eq!(state.add(fvm_macro::RawBytes::new(vec![0, 0])), None);
+    assert_eq!(state.count, 3);
+    assert_eq!(counter::local(), (vec![], Ok(7)));
+}
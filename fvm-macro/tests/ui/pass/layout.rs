@@ -0,0 +1,56 @@
+#[fvm_macro::contract]
+pub mod wrapped {
+    #[fvm_macro(state)]
+    pub struct Wrapped(pub u64);
+}
+
+#[fvm_macro::contract]
+pub mod empty {
+    #[fvm_macro(state)]
+    pub struct Empty;
+}
+
+#[fvm_macro::contract]
+pub mod owned {
+    #[fvm_macro(state, ownable, version = 1)]
+    pub struct Owned {
+        pub count: u64,
+    }
+
+    impl Owned {
+        #[fvm_macro(migrate(from = 0))]
+        fn from_v0(count: u64) -> Self {
+            Owned { count, ..Default::default() }
+        }
+    }
+}
+
+use fvm_macro::layout::{ChangeKind, Encoding, StorageLayout};
+
+fn main() {
+    // Serde encodes a single field tuple struct as its field, and a unit struct as `null`.
+    let wrapped = StorageLayout::from_json(wrapped::STORAGE_LAYOUT).unwrap();
+    assert_eq!(wrapped.encoding, Encoding::Newtype);
+    assert_eq!(wrapped.fields[0].name, "0");
+    let empty = StorageLayout::from_json(empty::STORAGE_LAYOUT).unwrap();
+    assert_eq!(empty.encoding, Encoding::Unit);
+    assert!(empty.fields.is_empty());
+    assert!(!wrapped.diff(&empty).is_compatible());
+
+    // The types of component fields are written with their public paths.
+    let owned = StorageLayout::from_json(owned::STORAGE_LAYOUT).unwrap();
+    let owner = owned.fields.iter().find(|field| field.name == "owner").unwrap();
+    assert_eq!(owner.ty, "::core::option::Option<fvm_macro::fvm_shared::ActorID>");
+
+    // Bumping the version without a migration from the old one is breaking.
+    let mut next = owned.clone();
+    next.version = Some(2);
+    let diff = owned.diff(&next);
+    assert!(!diff.is_compatible());
+    assert_eq!(
+        diff.changes[0].kind,
+        ChangeKind::VersionChanged { old: Some(1), new: Some(2) },
+    );
+    next.migrates_from.push(1);
+    assert!(owned.diff(&next).is_compatible());
+}
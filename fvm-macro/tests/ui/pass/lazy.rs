@@ -0,0 +1,49 @@
+#[fvm_macro::contract]
+pub mod registry {
+    use fvm_macro::*;
+
+    #[fvm_macro(state)]
+    pub struct Registry {
+        #[fvm_macro(immutable)]
+        pub symbol: String,
+        pub names: Lazy<Vec<String>>,
+    }
+
+    impl Registry {
+        #[fvm_macro(message)]
+        pub fn rename(&mut self) -> Option<RawBytes> {
+            self.symbol.push('!');
+            None
+        }
+    }
+}
+
+use cid::multihash::MultihashDigest;
+use fvm_macro::{from_slice, to_vec, Cid, Code, DAG_CBOR};
+use registry::Registry;
+
+fn main() {
+    // A decoded field keeps its link without fetching the value, and serializes the link again.
+    let cid = Cid::new_v1(DAG_CBOR, Code::Blake2b256.digest(b"names"));
+    let block = to_vec(&("FIL", cid)).unwrap();
+    let state: Registry = from_slice(&block).unwrap();
+    assert!(!state.names.is_loaded());
+    assert_eq!(state.names.cid(), Some(cid));
+    assert_eq!(to_vec(&state).unwrap(), block);
+
+    // A new value is only written by `Lazy::flush`, serializing it before is an error.
+    let state = Registry::default();
+    assert!(state.names.is_loaded());
+    assert_eq!(state.names.cid(), None);
+    assert!(to_vec(&state).is_err());
+
+    // The immutable fields are compared by value.
+    let mut state = Registry {
+        symbol: "FIL".to_string(),
+        ..Default::default()
+    };
+    let immutable = state.__immutable();
+    assert_eq!(immutable, ("FIL".to_string(),));
+    state.symbol.push('!');
+    assert_ne!(state.__immutable(), immutable);
+}
@@ -0,0 +1,38 @@
+#[fvm_macro::contract]
+pub mod vault {
+    #[fvm_macro(state, ownable)]
+    pub struct Vault {
+        pub balance: u64,
+    }
+}
+
+use cid::multihash::{Code, MultihashDigest};
+use fvm_macro::{blake2x256, selector_bytes, selector_id};
+
+// The macros expand to constants.
+const MINTED: [u8; 32] = blake2x256!("Minted");
+const OWNER_BYTES: [u8; 4] = selector_bytes!("Ownable::owner");
+const OWNER: u32 = selector_id!("Ownable::owner");
+
+fn main() {
+    let hash = Code::Blake2b256.digest(b"Minted");
+    assert_eq!(&MINTED[..], hash.digest());
+    assert_eq!(blake2x256!(b"Minted"), MINTED);
+
+    let hash = Code::Blake2b256.digest(b"Ownable::owner");
+    assert_eq!(&OWNER_BYTES[..], &hash.digest()[..4]);
+    assert_eq!(selector_bytes!(b"Ownable::owner"), OWNER_BYTES);
+    assert_eq!(OWNER, u32::from_be_bytes(OWNER_BYTES));
+    assert_eq!(selector_id!(b"Ownable::owner"), OWNER);
+
+    // The dispatcher numbers the component messages with the same selectors.
+    let abi: serde_json::Value = serde_json::from_str(vault::ABI).unwrap();
+    let owner = abi["abi"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|message| message["method"] == "owner")
+        .unwrap();
+    assert_eq!(owner["methodNumber"], (OWNER as u64).to_string());
+    assert!(vault::Vault::__mutates(selector_id!("Ownable::transfer_ownership") as u64));
+}
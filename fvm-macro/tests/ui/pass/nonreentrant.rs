@@ -0,0 +1,51 @@
+#[fvm_macro::contract]
+pub mod bank {
+    use fvm_macro::*;
+
+    #[fvm_macro(state)]
+    pub struct Bank {
+        pub balance: u64,
+    }
+
+    impl Bank {
+        #[fvm_macro(message, nonreentrant)]
+        pub fn withdraw(&mut self) -> Option<RawBytes> {
+            self.balance -= 1;
+            None
+        }
+
+        #[fvm_macro(message, nonreentrant)]
+        pub fn balance(&self) -> Option<RawBytes> {
+            None
+        }
+    }
+}
+
+use bank::Bank;
+use fvm_macro::{from_slice, to_vec};
+
+fn main() {
+    // Only the `&mut self` message saves the state, the lock is merely checked by the other.
+    let abi: serde_json::Value = serde_json::from_str(bank::ABI).unwrap();
+    for (method, mutates) in [("withdraw", true), ("balance", false)] {
+        let message = abi["abi"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|message| message["method"] == method)
+            .unwrap_or_else(|| panic!("missing {}", method));
+        assert_eq!(message["access"]["nonreentrant"], true, "{}", method);
+        let method_number: u64 = message["methodNumber"].as_str().unwrap().parse().unwrap();
+        assert_eq!(Bank::__mutates(method_number), mutates, "{}", method);
+    }
+
+    // The lock is stored with the user's fields and starts released.
+    assert!(!Bank::default().reentrancy_lock);
+    let state = Bank {
+        balance: 9,
+        reentrancy_lock: true,
+    };
+    let decoded: Bank = from_slice(&to_vec(&state).unwrap()).unwrap();
+    assert_eq!(decoded.balance, 9);
+    assert!(decoded.reentrancy_lock);
+}
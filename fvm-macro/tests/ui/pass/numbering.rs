@@ -0,0 +1,81 @@
+#[fvm_macro::contract]
+pub mod token {
+    use fvm_macro::*;
+
+    #[fvm_macro(state)]
+    pub struct Token {
+        pub supply: u64,
+    }
+
+    impl Token {
+        #[fvm_macro(message)]
+        pub fn mint(&mut self) -> Option<RawBytes> {
+            self.supply += 1;
+            None
+        }
+
+        #[fvm_macro(message)]
+        pub fn supply(&self) -> Option<RawBytes> {
+            None
+        }
+    }
+
+    #[fvm_macro(impl, namespace = "FRC42")]
+    impl Token {
+        #[fvm_macro(message)]
+        pub fn transfer(&mut self) -> Option<RawBytes> {
+            None
+        }
+    }
+
+    #[fvm_macro(impl, namespace = "Admin")]
+    impl Token {
+        #[fvm_macro(message)]
+        pub fn halt(&mut self) -> Option<RawBytes> {
+            None
+        }
+    }
+
+    // Continues the numbering of the first impl block without a namespace.
+    impl Token {
+        #[fvm_macro(message)]
+        pub fn burn(&mut self) -> Option<RawBytes> {
+            self.supply -= 1;
+            None
+        }
+    }
+}
+
+use fvm_macro::selector_id;
+use token::Token;
+
+fn main() {
+    let abi: serde_json::Value = serde_json::from_str(token::ABI).unwrap();
+    let method_number = |method: &str| -> u64 {
+        let message = abi["abi"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|message| message["method"] == method)
+            .unwrap_or_else(|| panic!("missing {}", method));
+        message["methodNumber"].as_str().unwrap().parse().unwrap()
+    };
+    assert_eq!(method_number("mint"), 2);
+    assert_eq!(method_number("supply"), 3);
+    assert_eq!(method_number("burn"), 4);
+
+    // The messages of namespaced impl blocks are grouped under their namespace.
+    assert!(abi["abi"].as_array().unwrap().iter().all(|message| message["method"] != "transfer"));
+    let transfer = &abi["namespaces"]["FRC42"][0];
+    assert_eq!(transfer["method"], "transfer");
+    assert_eq!(transfer["methodNumber"], (selector_id!("FRC42::transfer") as u64).to_string());
+    let halt = &abi["namespaces"]["Admin"][0];
+    assert_eq!(halt["method"], "halt");
+    assert_eq!(halt["methodNumber"], (selector_id!("Admin::halt") as u64).to_string());
+    assert_eq!(abi["namespaces"].as_object().unwrap().len(), 2);
+
+    assert!(Token::__mutates(2));
+    assert!(!Token::__mutates(3));
+    assert!(Token::__mutates(4));
+    assert!(!Token::__mutates(5));
+}
@@ -0,0 +1,53 @@
+#[fvm_macro::contract]
+pub mod vault {
+    use fvm_macro::*;
+
+    #[fvm_macro(state, ownable)]
+    pub struct Vault {
+        pub balance: u64,
+    }
+
+    impl Vault {
+        #[fvm_macro(message, only = "owner")]
+        pub fn deposit(&mut self) -> Option<RawBytes> {
+            self.balance += 1;
+            None
+        }
+    }
+}
+
+use fvm_macro::{from_slice, selector_id, to_vec};
+use vault::Vault;
+
+fn main() {
+    // The component messages are dispatched under the hash of `Ownable::<method>`.
+    let abi: serde_json::Value = serde_json::from_str(vault::ABI).unwrap();
+    for (method, mutates) in [
+        ("owner", false),
+        ("transferOwnership", true),
+        ("acceptOwnership", true),
+        ("renounceOwnership", true),
+        ("deposit", true),
+    ] {
+        let message = abi["abi"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|message| message["method"] == method)
+            .unwrap_or_else(|| panic!("missing {}", method));
+        let method_number: u64 = message["methodNumber"].as_str().unwrap().parse().unwrap();
+        assert_eq!(Vault::__mutates(method_number), mutates, "{}", method);
+    }
+    assert!(Vault::__mutates(selector_id!("Ownable::transfer_ownership") as u64));
+
+    // The owner and the pending owner are stored with the user's fields.
+    let state = Vault {
+        balance: 3,
+        owner: Some(100),
+        pending_owner: Some(101),
+    };
+    let decoded: Vault = from_slice(&to_vec(&state).unwrap()).unwrap();
+    assert_eq!(decoded.balance, 3);
+    assert_eq!(decoded.owner, Some(100));
+    assert_eq!(decoded.pending_owner, Some(101));
+}
@@ -0,0 +1,58 @@
+#[fvm_macro::contract]
+pub mod token {
+    use fvm_macro::*;
+
+    #[fvm_macro(state, ownable, pausable)]
+    pub struct Token {
+        pub supply: u64,
+    }
+
+    impl Token {
+        #[fvm_macro(message, when_not_paused)]
+        pub fn mint(&mut self) -> Option<RawBytes> {
+            self.supply += 1;
+            None
+        }
+
+        #[fvm_macro(message)]
+        pub fn supply(&self) -> Option<RawBytes> {
+            None
+        }
+    }
+}
+
+use fvm_macro::{from_slice, selector_id, to_vec};
+use token::Token;
+
+fn main() {
+    // The component messages are dispatched under the hash of `Pausable::<method>`.
+    let abi: serde_json::Value = serde_json::from_str(token::ABI).unwrap();
+    for (method, mutates) in [
+        ("paused", false),
+        ("pause", true),
+        ("unpause", true),
+        ("mint", true),
+        ("supply", false),
+    ] {
+        let message = abi["abi"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|message| message["method"] == method)
+            .unwrap_or_else(|| panic!("missing {}", method));
+        let method_number: u64 = message["methodNumber"].as_str().unwrap().parse().unwrap();
+        assert_eq!(Token::__mutates(method_number), mutates, "{}", method);
+    }
+    assert!(Token::__mutates(selector_id!("Pausable::pause") as u64));
+
+    // The flag is stored with the user's fields and starts unpaused.
+    assert!(!Token::default().paused);
+    let state = Token {
+        supply: 5,
+        paused: true,
+        ..Default::default()
+    };
+    let decoded: Token = from_slice(&to_vec(&state).unwrap()).unwrap();
+    assert_eq!(decoded.supply, 5);
+    assert!(decoded.paused);
+}
@@ -0,0 +1,49 @@
+#[fvm_macro::contract(rbac)]
+pub mod roles {
+    use fvm_macro::*;
+
+    #[fvm_macro(state)]
+    pub struct Roles {
+        pub count: u64,
+    }
+
+    impl Roles {
+        #[fvm_macro(message, roles = "minter")]
+        pub fn mint(&mut self) -> Option<RawBytes> {
+            self.count += 1;
+            None
+        }
+    }
+}
+
+use fvm_macro::{from_slice, rbac::Rbac, selector_id, to_vec};
+use roles::Roles;
+
+fn main() {
+    // The component messages are dispatched under the hash of `Rbac::<method>`.
+    let abi: serde_json::Value = serde_json::from_str(roles::ABI).unwrap();
+    for (method, mutates) in [
+        ("grantRole", true),
+        ("revokeRole", true),
+        ("renounceRole", true),
+        ("setRoleAdmin", true),
+        ("hasRole", false),
+    ] {
+        let message = abi["abi"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|message| message["method"] == method)
+            .unwrap_or_else(|| panic!("missing {}", method));
+        let method_number: u64 = message["methodNumber"].as_str().unwrap().parse().unwrap();
+        assert_eq!(Roles::__mutates(method_number), mutates, "{}", method);
+    }
+    assert!(Roles::__mutates(selector_id!("Rbac::grant_role") as u64));
+
+    // The role assignments are stored as the root of their HAMT, `null` while empty.
+    let state = Roles { count: 7, ..Default::default() };
+    let decoded: Roles = from_slice(&to_vec(&state).unwrap()).unwrap();
+    assert_eq!(decoded.count, 7);
+    assert_eq!(decoded.rbac, Rbac::default());
+    assert_eq!(decoded.rbac.clone(), decoded.rbac);
+}
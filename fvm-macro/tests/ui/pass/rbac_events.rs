@@ -0,0 +1,158 @@
+#[fvm_macro::contract(rbac)]
+pub mod roles {
+    use fvm_macro::*;
+
+    #[fvm_macro(state)]
+    pub struct Roles {
+        pub count: u64,
+    }
+
+    impl Roles {
+        #[fvm_macro(message, roles = "minter")]
+        pub fn mint(&mut self) -> Option<RawBytes> {
+            self.count += 1;
+            None
+        }
+    }
+}
+
+use std::sync::Mutex;
+
+use fvm_macro::fvm_sdk::sys::vm::InvocationContext;
+use fvm_macro::fvm_shared::address::Address;
+use fvm_macro::rbac::{RoleAdminChanged, RoleAdminParams, RoleGranted, RoleParams, RoleRevoked};
+use fvm_macro::{from_slice, Event, RawBytes};
+use roles::Roles;
+
+// The syscalls the role messages make, stubbed so that they run natively. The caller is always
+// actor 100 and the debug log, which records the events, is kept in `LOG`.
+const CALLER: u64 = 100;
+
+static LOG: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+#[no_mangle]
+unsafe extern "C" fn context(ret: *mut InvocationContext) -> u32 {
+    let mut context: InvocationContext = std::mem::zeroed();
+    context.caller = CALLER;
+    ret.write(context);
+    0
+}
+
+#[no_mangle]
+unsafe extern "C" fn resolve_address(ret: *mut u64, addr_off: *const u8, addr_len: u32) -> u32 {
+    let bytes = std::slice::from_raw_parts(addr_off, addr_len as usize);
+    ret.write(Address::from_bytes(bytes).unwrap().id().unwrap());
+    0
+}
+
+#[no_mangle]
+unsafe extern "C" fn enabled(ret: *mut i32) -> u32 {
+    ret.write(1);
+    0
+}
+
+#[no_mangle]
+unsafe extern "C" fn log(message: *const u8, message_len: u32) -> u32 {
+    let message = std::slice::from_raw_parts(message, message_len as usize);
+    LOG.lock().unwrap().push(String::from_utf8(message.to_vec()).unwrap());
+    0
+}
+
+// The roles start out empty, so their HAMT is never read.
+#[no_mangle]
+extern "C" fn block_open() -> u32 {
+    unreachable!()
+}
+
+#[no_mangle]
+extern "C" fn block_read() -> u32 {
+    unreachable!()
+}
+
+/// Returns the events logged since the last call, as their name, topics and data.
+fn take_events() -> Vec<(String, Vec<String>, Vec<u8>)> {
+    LOG.lock()
+        .unwrap()
+        .drain(..)
+        .map(|line| {
+            let mut parts = line.splitn(4, ' ');
+            assert_eq!(parts.next(), Some("event"));
+            let name = parts.next().unwrap().to_string();
+            let topics = parts.next().unwrap().trim_matches(|c| c == '[' || c == ']');
+            let topics = topics.split(',').map(str::to_string).collect();
+            (name, topics, from_hex(parts.next().unwrap()))
+        })
+        .collect()
+}
+
+fn from_hex(hex: &str) -> Vec<u8> {
+    (0..hex.len())
+        .step_by(2)
+        .map(|index| u8::from_str_radix(&hex[index..index + 2], 16).unwrap())
+        .collect()
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+fn topics<E: Event>(event: &E) -> Vec<String> {
+    event.topics().iter().map(|topic| to_hex(topic.bytes())).collect()
+}
+
+fn role_params(role: &str, account: u64) -> RawBytes {
+    RawBytes::serialize(RoleParams { role: role.to_string(), account: Address::new_id(account) }).unwrap()
+}
+
+fn main() {
+    let mut state = Roles::default();
+    state.rbac.grant_role("admin", CALLER);
+
+    // Granting a role emits `RoleGranted`, with the role and both accounts as topics.
+    assert_eq!(state.grant_role(role_params("minter", 200)), None);
+    assert!(state.rbac.has_role("minter", 200));
+    let granted = RoleGranted { role: "minter".to_string(), account: 200, sender: CALLER };
+    let events = take_events();
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].0, "RoleGranted");
+    assert_eq!(events[0].1, topics(&granted));
+    assert_eq!(from_slice::<RoleGranted>(&events[0].2).unwrap(), granted);
+
+    // Granting it again changes nothing and emits nothing.
+    state.grant_role(role_params("minter", 200));
+    assert!(take_events().is_empty());
+
+    // Revoking and renouncing emit `RoleRevoked`, renouncing with the account as the sender.
+    state.revoke_role(role_params("minter", 200));
+    state.grant_role(role_params("minter", CALLER));
+    state.renounce_role(role_params("minter", CALLER));
+    let events = take_events();
+    assert_eq!(events.len(), 3);
+    assert_eq!(events[0].0, "RoleRevoked");
+    assert_eq!(
+        from_slice::<RoleRevoked>(&events[0].2).unwrap(),
+        RoleRevoked { role: "minter".to_string(), account: 200, sender: CALLER },
+    );
+    assert_eq!(events[1].0, "RoleGranted");
+    assert_eq!(events[2].0, "RoleRevoked");
+    assert_eq!(
+        from_slice::<RoleRevoked>(&events[2].2).unwrap(),
+        RoleRevoked { role: "minter".to_string(), account: CALLER, sender: CALLER },
+    );
+    assert!(!state.rbac.has_role("minter", CALLER));
+
+    // Changing the admin role emits `RoleAdminChanged` with the previous and the new one.
+    let params = RoleAdminParams { role: "minter".to_string(), admin_role: "ops".to_string() };
+    state.set_role_admin(RawBytes::serialize(params).unwrap());
+    let changed = RoleAdminChanged {
+        role: "minter".to_string(),
+        previous_admin_role: "admin".to_string(),
+        new_admin_role: "ops".to_string(),
+    };
+    let events = take_events();
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].0, "RoleAdminChanged");
+    assert_eq!(events[0].1, topics(&changed));
+    assert_eq!(from_slice::<RoleAdminChanged>(&events[0].2).unwrap(), changed);
+    assert_eq!(state.rbac.role_admin("minter"), "ops");
+}
@@ -0,0 +1,60 @@
+#[fvm_macro::contract]
+pub mod token {
+    use fvm_macro::*;
+
+    #[fvm_macro(state)]
+    pub struct Token {
+        pub supply: u64,
+    }
+
+    impl Token {
+        #[fvm_macro(message)]
+        pub fn mint(&mut self) -> Option<RawBytes> {
+            self.supply += 1;
+            None
+        }
+
+        #[fvm_macro(message, selector = 0x0000002A)]
+        pub fn supply(&self) -> Option<RawBytes> {
+            None
+        }
+
+        #[fvm_macro(message, selector = 0xCAFE0001)]
+        pub fn burn(&mut self) -> Option<RawBytes> {
+            self.supply -= 1;
+            None
+        }
+
+        #[fvm_macro(message)]
+        pub fn reset(&mut self) -> Option<RawBytes> {
+            self.supply = 0;
+            None
+        }
+    }
+}
+
+use token::Token;
+
+fn main() {
+    let abi: serde_json::Value = serde_json::from_str(token::ABI).unwrap();
+    let method_number = |method: &str| -> u64 {
+        let message = abi["abi"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|message| message["method"] == method)
+            .unwrap_or_else(|| panic!("missing {}", method));
+        message["methodNumber"].as_str().unwrap().parse().unwrap()
+    };
+    // Messages with a selector are numbered by it, the others keep counting from 2.
+    assert_eq!(method_number("mint"), 2);
+    assert_eq!(method_number("supply"), 0x2A);
+    assert_eq!(method_number("burn"), 0xCAFE0001);
+    assert_eq!(method_number("reset"), 3);
+
+    assert!(Token::__mutates(2));
+    assert!(!Token::__mutates(0x2A));
+    assert!(Token::__mutates(0xCAFE0001));
+    assert!(Token::__mutates(3));
+    assert!(!Token::__mutates(4));
+}
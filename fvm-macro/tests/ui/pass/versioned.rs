@@ -0,0 +1,93 @@
+#[fvm_macro::contract]
+pub mod counter {
+    use fvm_macro::*;
+
+    #[derive(serde::Serialize, serde::Deserialize)]
+    pub struct CounterV0 {
+        pub count: u64,
+    }
+
+    #[derive(serde::Serialize, serde::Deserialize)]
+    pub struct CounterV1 {
+        pub count: u64,
+        pub step: u64,
+    }
+
+    #[fvm_macro(state, version = 2)]
+    pub struct Counter {
+        pub count: u64,
+        pub step: u64,
+        pub label: String,
+    }
+
+    impl Counter {
+        #[fvm_macro(migrate(from = 0))]
+        fn from_v0(old: CounterV0) -> CounterV1 {
+            CounterV1 { count: old.count, step: 1 }
+        }
+
+        #[fvm_macro(migrate(from = 1))]
+        fn from_v1(old: CounterV1) -> Self {
+            Counter { count: old.count, step: old.step, label: String::new() }
+        }
+
+        #[fvm_macro(message)]
+        pub fn count(&self) -> Option<RawBytes> {
+            None
+        }
+    }
+}
+
+use counter::{Counter, CounterV0, CounterV1};
+use fvm_macro::{from_slice, to_vec, version};
+
+#[derive(serde::Deserialize)]
+struct Envelope {
+    #[serde(rename = "fvm_macro::version")]
+    version: u64,
+    #[serde(rename = "fvm_macro::state")]
+    state: Counter,
+}
+
+fn main() {
+    // The state is written in an envelope keyed by names no state field can have.
+    let state = Counter { count: 3, step: 2, label: "c".to_string() };
+    let block = to_vec(&version::Versioned { version: 2, state: &state }).unwrap();
+    assert_eq!(version::of(&block), Some(2));
+    let envelope: Envelope = from_slice(&block).unwrap();
+    assert_eq!(envelope.version, 2);
+    assert_eq!(envelope.state.count, 3);
+    assert_eq!(envelope.state.step, 2);
+    assert_eq!(envelope.state.label, "c");
+
+    // Unversioned roots are never mistaken for versioned ones, even `[u64, _]` shaped ones.
+    assert_eq!(version::of(&to_vec(&(3u64, 1u64)).unwrap()), None);
+    assert_eq!(version::of(&to_vec(&state).unwrap()), None);
+
+    // A root written before the state was versioned runs both migrations.
+    let (migrated, was_migrated) = Counter::__decode(&to_vec(&CounterV0 { count: 5 }).unwrap());
+    assert!(was_migrated);
+    assert_eq!(migrated.count, 5);
+    assert_eq!(migrated.step, 1);
+    assert_eq!(migrated.label, "");
+
+    // A root written at version 1 runs the migration from 1 to 2 only.
+    let v1 = CounterV1 { count: 6, step: 4 };
+    let (migrated, was_migrated) =
+        Counter::__decode(&to_vec(&version::Versioned { version: 1, state: &v1 }).unwrap());
+    assert!(was_migrated);
+    assert_eq!(migrated.count, 6);
+    assert_eq!(migrated.step, 4);
+    assert_eq!(migrated.label, "");
+
+    // A root written at the current version is decoded as is.
+    let (decoded, was_migrated) = Counter::__decode(&block);
+    assert!(!was_migrated);
+    assert_eq!(decoded.count, 3);
+    assert_eq!(decoded.step, 2);
+    assert_eq!(decoded.label, "c");
+
+    // The migration message is generated and always saves.
+    let migrate = fvm_macro::selector_id!("Migration::migrate") as u64;
+    assert!(Counter::__mutates(migrate));
+}
@@ -1,4 +1,4 @@
-#[fvm_macro::contract]
+#[fvm_macro::contract(entry)]
 pub mod hello_world {
     use fvm_macro::*;
 